@@ -1,3 +1,10 @@
+//! Locate Steam and the apps it has installed.
+//!
+//! Optional features:
+//! - `serde`: `Serialize` and `Deserialize` for [`App`], deserializing any
+//!   type from a [`VdfValue`] and `vdf_to_string`
+//! - `typed-arena`: `vdf_parse_in_arena`
+
 use std::borrow::Cow;
 use std::fs;
 use std::fmt::Write;
@@ -6,8 +13,135 @@ use std::str::Chars;
 use std::path::Path;
 use std::path::PathBuf;
 
+/// Common types and entry points, for `use steam_find::prelude::*`.
+pub mod prelude {
+    pub use crate::App;
+    pub use crate::AppId;
+    pub use crate::AppType;
+    pub use crate::Shortcut;
+    pub use crate::SteamUser;
+    pub use crate::VdfParserOptions;
+    pub use crate::VdfValue;
+    pub use crate::vdf_parse;
+    pub use crate::vdf_parse_binary;
+    pub use crate::get_steam_app;
+    pub use crate::steam_apps;
+    pub use crate::steam_dir;
+    pub use crate::SteamError;
+    pub use crate::SteamLibrary;
+    pub use crate::SteamResult;
+}
+
+pub type SteamResult<T> = Result<T, SteamError>;
+
+#[derive(Debug)]
+pub enum SteamError {
+    /// No Steam install was found, holding the default location that was
+    /// checked.
+    NotInstalled(PathBuf),
+    /// A library's `steamapps/` directory is missing.
+    LibraryNotFound(PathBuf),
+    Io(io::Error),
+    /// A VDF or manifest file is malformed.
+    ParseError { path: PathBuf, message: String },
+}
+
+impl SteamError {
+    fn parse(path: &Path, message: impl std::fmt::Display) -> SteamError {
+        SteamError::ParseError {
+            path: path.to_path_buf(),
+            message: message.to_string(),
+        }
+    }
+}
+
+impl std::fmt::Display for SteamError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SteamError::NotInstalled(path) => write!(f, "failed to find Steam install at {}", path.display()),
+            SteamError::LibraryNotFound(path) => write!(f, "failed to find library {}", path.display()),
+            SteamError::Io(err) => err.fmt(f),
+            SteamError::ParseError { path, message } => write!(f, "{}: {}", path.display(), message),
+        }
+    }
+}
+
+impl std::error::Error for SteamError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SteamError::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for SteamError {
+    fn from(err: io::Error) -> Self {
+        SteamError::Io(err)
+    }
+}
+
+// prefixes errors with the path they came from, keeping the error kind
+fn with_path(path: &Path) -> impl FnOnce(io::Error) -> io::Error + '_ {
+    move |err| io::Error::new(err.kind(), format!("{}: {}", path.display(), err))
+}
+
+/// The Steam install directory, the first of [`steam_dir_all`].
+///
+/// Setting the `STEAM_DIR` environment variable skips the lookup and uses its
+/// value as is, e.g. to point tests at a fake install.
+pub fn steam_dir() -> SteamResult<PathBuf> {
+    if let Some(path) = steam_dir_from_env() {
+        return Ok(path);
+    }
+    let candidates = candidate_steam_dirs();
+    let default = candidates.first().cloned().unwrap_or_else(|| PathBuf::from(DEFAULT_STEAM_DIR));
+    installed_steam_dirs(candidates)
+        .into_iter()
+        .next()
+        .ok_or(SteamError::NotInstalled(default))
+}
+
+// named in the `NotInstalled` error when there is nothing to probe, e.g.
+// without `$HOME`
+#[cfg(target_os = "windows")]
+const DEFAULT_STEAM_DIR: &str = r"C:\Program Files (x86)\Steam";
+#[cfg(target_os = "macos")]
+const DEFAULT_STEAM_DIR: &str = "~/Library/Application Support/Steam";
+#[cfg(all(not(target_os = "windows"), not(target_os = "macos")))]
+const DEFAULT_STEAM_DIR: &str = "~/.steam/steam";
+
+/// The `STEAM_DIR` override [`steam_dir`] checks first, if it is set and not
+/// empty.
+pub fn steam_dir_from_env() -> Option<PathBuf> {
+    std::env::var_os("STEAM_DIR").filter(|path| !path.is_empty()).map(PathBuf::from)
+}
+
+/// Every Steam install found in the usual places for the platform, most
+/// likely first, e.g. a native and a Flatpak install. Locations with no
+/// `libraryfolders.vdf` are skipped, as are symlinks to ones already listed.
+pub fn steam_dir_all() -> Vec<PathBuf> {
+    installed_steam_dirs(candidate_steam_dirs())
+}
+
+fn installed_steam_dirs(candidates: Vec<PathBuf>) -> Vec<PathBuf> {
+    let mut seen = Vec::new();
+    let mut dirs = Vec::new();
+    for path in candidates {
+        if !path.join("steamapps").join("libraryfolders.vdf").exists() {
+            continue;
+        }
+        let real = real_path(&path);
+        if !seen.contains(&real) {
+            seen.push(real);
+            dirs.push(path);
+        }
+    }
+    dirs
+}
+
 #[cfg(target_os = "windows")]
-pub fn steam_dir() -> io::Result<PathBuf> {
+fn candidate_steam_dirs() -> Vec<PathBuf> {
     use std::mem;
     use std::ffi::c_void;
     use std::ffi::OsString;
@@ -33,41 +167,75 @@ pub fn steam_dir() -> io::Result<PathBuf> {
 
     const BUFFER_SIZE: usize = 1024;
 
-    let mut buffer: [u16; BUFFER_SIZE] = [0; BUFFER_SIZE];
-    let mut size = (BUFFER_SIZE * mem::size_of_val(&buffer[0])) as u32;
-    let mut kind = 0;
-    unsafe {
-        if RegGetValueW(
-            HKEY_CURRENT_USER,
-            OsString::from("SOFTWARE\\Valve\\Steam\0").encode_wide().collect::<Vec<_>>().as_ptr() as *const _,
-            OsString::from("SteamPath\0").encode_wide().collect::<Vec<_>>().as_ptr() as *const _,
-            RRF_RT_REG_SZ,
-            &mut kind,
-            buffer.as_mut_ptr() as *mut _,
-            &mut size,
-        ) == 0 || RegGetValueW(
-            HKEY_LOCAL_MACHINE,
-            OsString::from("SOFTWARE\\WOW6432Node\\Valve\\Steam\0").encode_wide().collect::<Vec<_>>().as_ptr() as *const _,
-            OsString::from("InstallPath\0").encode_wide().collect::<Vec<_>>().as_ptr() as *const _,
-            RRF_RT_REG_SZ,
-            &mut kind,
-            buffer.as_mut_ptr() as *mut _,
-            &mut size,
-        ) == 0 {
-            let len = (size as usize - 1) / 2;
-            let path = PathBuf::from(OsString::from_wide(&buffer[..len]));
-
-            return Ok(path);
+    fn wide(s: &str) -> Vec<u16> {
+        OsString::from(s).encode_wide().chain(Some(0)).collect()
+    }
+
+    let query = |hkey: isize, subkey: &str, value: &str| {
+        let mut buffer: [u16; BUFFER_SIZE] = [0; BUFFER_SIZE];
+        let mut size = (BUFFER_SIZE * mem::size_of_val(&buffer[0])) as u32;
+        let mut kind = 0;
+        let res = unsafe {
+            RegGetValueW(
+                hkey,
+                wide(subkey).as_ptr(),
+                wide(value).as_ptr(),
+                RRF_RT_REG_SZ,
+                &mut kind,
+                buffer.as_mut_ptr() as *mut _,
+                &mut size,
+            )
+        };
+        if res == 0 {
+            let len = (size as usize / 2).saturating_sub(1);
+            Some(PathBuf::from(OsString::from_wide(&buffer[..len])))
+        } else {
+            None
         }
+    };
+
+    // per-user key first, then the machine-wide keys written by "all users"
+    // installs, native and 32-bit WoW64 view
+    let keys = [
+        (HKEY_CURRENT_USER, "SOFTWARE\\Valve\\Steam", "SteamPath"),
+        (HKEY_LOCAL_MACHINE, "SOFTWARE\\Valve\\Steam", "InstallPath"),
+        (HKEY_LOCAL_MACHINE, "SOFTWARE\\WOW6432Node\\Valve\\Steam", "InstallPath"),
+    ];
+    let mut candidates = keys
+        .into_iter()
+        .filter_map(|(hkey, subkey, value)| query(hkey, subkey, value))
+        .collect::<Vec<_>>();
+
+    // portable installs leave the registry alone and write a `steam.cfg`
+    let configs = [
+        ("APPDATA", r"Valve\Steam\steam.cfg"),
+        ("PROGRAMFILES(X86)", r"Steam\steam.cfg"),
+        ("PROGRAMFILES", r"Steam\steam.cfg"),
+    ];
+    for (var, file) in configs {
+        let Some(dir) = std::env::var_os(var) else {
+            continue;
+        };
+        candidates.extend(fs::read_to_string(Path::new(&dir).join(file)).ok().and_then(|cfg| parse_steam_cfg(&cfg)));
     }
 
     if let Some(home) = std::env::var_os("WINEHOMEDIR") {
         let mut path = PathBuf::from(home);
         path.push(r".steam\steam");
-        return Ok(path);
+        candidates.push(path);
     }
+    candidates
+}
 
-    Err(io::Error::new(io::ErrorKind::NotFound, "failed to find Steam"))
+// `SteamPath` from the `key=value` lines of a `steam.cfg`
+#[cfg(any(target_os = "windows", test))]
+fn parse_steam_cfg(cfg: &str) -> Option<PathBuf> {
+    cfg.lines()
+        .filter_map(|line| line.split_once('='))
+        .find(|(key, _)| key.trim().eq_ignore_ascii_case("SteamPath"))
+        .map(|(_, value)| value.trim().trim_matches('"'))
+        .filter(|value| !value.is_empty())
+        .map(PathBuf::from)
 }
 
 #[cfg(not(target_os = "windows"))]
@@ -76,267 +244,4029 @@ fn home_dir() -> io::Result<std::ffi::OsString> {
 }
 
 #[cfg(target_os = "macos")]
-pub fn steam_dir() -> io::Result<PathBuf> {
-    let home = home_dir()?;
-    let mut path = PathBuf::with_capacity(home.len() + 64);
-    path.push(home);
-    path.push("Library");
-    path.push("Application Support");
-    path.push("Steam");
-    Ok(path)
+fn candidate_steam_dirs() -> Vec<PathBuf> {
+    // per-user install first, then a machine-wide one
+    let mut candidates = Vec::new();
+    if let Ok(home) = home_dir() {
+        candidates.push(Path::new(&home).join("Library/Application Support/Steam"));
+    }
+    candidates.push(PathBuf::from("/Library/Application Support/Steam"));
+    candidates
 }
 
 #[cfg(all(not(target_os = "windows"), not(target_os = "macos")))]
-pub fn steam_dir() -> io::Result<PathBuf> {
-    let home = home_dir()?;
-    let mut path = PathBuf::with_capacity(home.len() + 64);
-    path.push(home);
-    path.push(".steam");
-    path.push("steam");
-    Ok(path)
+fn candidate_steam_dirs() -> Vec<PathBuf> {
+    match home_dir() {
+        Ok(home) => steam_dir_candidates(&home, std::env::var_os("XDG_DATA_HOME")),
+        Err(_) => Vec::new(),
+    }
 }
 
-pub fn steam_apps() -> io::Result<Vec<App>> {
-    let mut steam = steam_dir()?;
-    steam.push("steamapps");
-    let lib = steam.join("libraryfolders.vdf");
-    let buffer = fs::read_to_string(&lib)?;
-    let lib = vdf_parse(buffer.chars())?;
-    let mut libraries = Vec::new();
-    for (_key, map) in lib["libraryfolders"].iter() {
-        if let Some(path) = map["path"].as_str() {
-            let path = Path::new(&path).join("steamapps");
-            libraries.push(path);
-        }
+// XDG data dirs first, then the legacy `~/.steam` links, then Flatpak and Snap
+#[cfg(all(not(target_os = "windows"), not(target_os = "macos")))]
+fn steam_dir_candidates(
+    home: &std::ffi::OsStr,
+    xdg_data_home: Option<std::ffi::OsString>,
+) -> Vec<PathBuf> {
+    let home = Path::new(home);
+    let mut candidates = vec![data_home(home.as_os_str(), xdg_data_home).join("Steam")];
+    let local_share = home.join(".local").join("share").join("Steam");
+    if candidates[0] != local_share {
+        candidates.push(local_share);
     }
+    candidates.extend([
+        home.join(".steam").join("steam"),
+        home.join(".steam").join("root"),
+        home.join(".var/app/com.valvesoftware.Steam/.steam/steam"),
+        home.join("snap/steam/common/.steam/steam"),
+    ]);
+    candidates
+}
+
+// the XDG spec requires `$XDG_DATA_HOME` to be absolute and says to ignore
+// it otherwise
+#[cfg(all(not(target_os = "windows"), not(target_os = "macos")))]
+fn data_home(home: &std::ffi::OsStr, xdg_data_home: Option<std::ffi::OsString>) -> PathBuf {
+    match xdg_data_home.map(PathBuf::from) {
+        Some(path) if path.is_absolute() => path,
+        _ => Path::new(home).join(".local").join("share"),
+    }
+}
+
+/// Every installed app, sorted by app ID.
+///
+/// A fresh install without games gives an empty list, also when
+/// `libraryfolders.vdf` has no `libraryfolders` section at all. Use
+/// [`steam_apps_iter`] to read manifests lazily instead.
+///
+/// An app found in several libraries, e.g. left behind by an interrupted
+/// move, is returned once, keeping the copy with the largest `size_on_disk`.
+pub fn steam_apps() -> SteamResult<Vec<App>> {
+    let mut apps = steam_libraries_with_apps()?
+        .into_iter()
+        .flat_map(|library| library.apps)
+        .collect::<Vec<_>>();
+    dedup_apps(&mut apps);
+    Ok(apps)
+}
 
+// sorts by app ID, keeping the copy with the largest `size_on_disk` of apps
+// found in several libraries
+fn dedup_apps(apps: &mut Vec<App>) {
+    apps.sort_unstable_by_key(|app| (app.app_id, std::cmp::Reverse(app.size_on_disk)));
+    apps.dedup_by_key(|app| app.app_id);
+}
+
+// `steam_apps` with `filter` checked against the `AppState` block before an
+// `App` is built
+fn filter_apps(filter: &mut dyn FnMut(&VdfValue) -> bool) -> SteamResult<Vec<App>> {
     let mut apps = Vec::new();
-    for path in libraries.iter() {
-        let root = path.join("common");
-        for fd in fs::read_dir(path)? {
-            let path = fd?.path();
-            if path.extension().and_then(|os| os.to_str()) == Some("acf") {
-                let buffer = fs::read_to_string(path)?;
-                let ast = vdf_parse(buffer.chars())?;
-                let state = &ast["AppState"];
-
-                if let Some(app) = (|| {
-                    Some(App {
-                        app_id: state["appid"].as_int()? as u64,
-                        size_on_disk: state["SizeOnDisk"].as_int()? as u64,
-                        path: root.join(state["installdir"].as_str()?),
-                        name: state["name"].as_str()?.to_string(),
-                    })
-                })() {
-                    apps.push(app);
-                }
-            }
-        }
+    for path in steam_libraries()? {
+        apps.extend(library_apps(&path, &mut *filter).filter_map(|app| app.ok()));
     }
-    apps.sort_unstable_by(|a, b| a.app_id.cmp(&b.app_id));
+    dedup_apps(&mut apps);
     Ok(apps)
 }
 
-pub fn get_steam_app(app_id: u64) -> io::Result<App> {
-    let mut steam = steam_dir()?;
-    steam.push("steamapps");
-    let lib = steam.join("libraryfolders.vdf");
-    let buffer = fs::read_to_string(&lib)?;
-    let lib = vdf_parse(buffer.chars())?;
-    for (_key, map) in lib["libraryfolders"].iter() {
-        for (entry_app_id, _) in map["apps"].iter() {
-            if let Ok(target_id) = u64::from_str_radix(entry_app_id, 10) {
-                if target_id != app_id {
-                    continue;
-                }
+/// A `steamapps/` directory and the apps installed in it.
+#[derive(Debug)]
+pub struct SteamLibrary {
+    pub path: PathBuf,
+    pub apps: Vec<App>,
+    // size of the library's `apps` list in `libraryfolders.vdf`, `None` if
+    // it isn't listed there
+    app_count: Option<usize>,
+}
 
-                if let Some(path) = map["path"].as_str() {
-                    let mut path = path.to_string();
-                    write!(&mut path, "/steamapps/").unwrap();
-                    let len = path.len();
-                    write!(&mut path, "appmanifest_{target_id}.acf").unwrap();
-                    let buffer = fs::read_to_string(&path)?;
-                    path.truncate(len);
-                    path.push_str("common/");
-                    let ast = vdf_parse(buffer.chars())?;
-                    let state = &ast["AppState"];
-
-                    if let Some(app) = (|| Some(App {
-                            app_id: state["appid"].as_int()? as u64,
-                            size_on_disk: state["SizeOnDisk"].as_int()? as u64,
-                            path: Path::new(&path).join(state["installdir"].as_str()?),
-                            name: state["name"].as_str()?.to_string(),
-                    }))() {
-                        return Ok(app);
-                    }
-                }
-            }
+impl SteamLibrary {
+    /// The library folder holding `steamapps/`, as listed in
+    /// `libraryfolders.vdf`.
+    #[doc(alias = "path")]
+    pub fn root(&self) -> &Path {
+        self.path.parent().unwrap_or(&self.path)
+    }
+
+    /// The `steamapps/` directory, same as the `path` field.
+    pub fn steamapps_path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Number of apps `libraryfolders.vdf` listed for this library when it
+    /// was read, without reading any manifests. `None` for a default library
+    /// that `libraryfolders.vdf` doesn't list.
+    pub fn total_app_count(&self) -> Option<usize> {
+        self.app_count
+    }
+}
+
+impl IntoIterator for SteamLibrary {
+    type Item = App;
+    type IntoIter = std::vec::IntoIter<App>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.apps.into_iter()
+    }
+}
+
+/// Apps grouped by library, skipping manifests that fail to parse.
+pub fn steam_libraries_with_apps() -> SteamResult<Vec<SteamLibrary>> {
+    let lib = libraryfolders_vdf()?;
+    let counts = library_entries(&lib)
+        .filter_map(|map| {
+            let path = Path::new(map["path"].as_str()?).join("steamapps");
+            Some((real_path(&path), map["apps"].len()))
+        })
+        .collect::<Vec<_>>();
+    Ok(library_paths(&lib)?
+        .into_iter()
+        .map(|path| {
+            let mut apps = library_apps(&path, |_| true).filter_map(|app| app.ok()).collect::<Vec<_>>();
+            apps.sort_unstable_by_key(|app| app.app_id);
+            let real = real_path(&path);
+            let app_count = counts.iter().find(|(listed, _)| *listed == real).map(|&(_, count)| count);
+            SteamLibrary { path, apps, app_count }
+        })
+        .collect())
+}
+
+/// Lazy version of [`steam_apps`] that only opens the next manifest when
+/// asked for another app, yielding errors for unreadable libraries and
+/// manifests instead of skipping them.
+pub fn steam_apps_iter() -> SteamResult<impl Iterator<Item = SteamResult<App>>> {
+    Ok(steam_libraries()?.into_iter().flat_map(|library| library_apps(&library, |_| true)))
+}
+
+// lazily reads the manifests in one `steamapps/` directory that pass `filter`
+fn library_apps(
+    library: &Path,
+    mut filter: impl FnMut(&VdfValue) -> bool,
+) -> impl Iterator<Item = SteamResult<App>> {
+    let root = library.join("common");
+    let has_common = root.is_dir();
+    let (entries, error) = match read_library(library) {
+        Ok(entries) => (Some(entries), None),
+        Err(err) => (None, Some(Err(err))),
+    };
+    error.into_iter().chain(entries.into_iter().flatten().filter_map(move |fd| {
+        match fd {
+            Ok(fd) => read_manifest(&fd.path(), &root, has_common, false, &mut filter).transpose(),
+            Err(err) => Some(Err(err.into())),
+        }
+    }))
+}
+
+/// Like [`steam_apps`] but returns an error for manifests that look corrupted
+/// instead of passing them through, and skips apps claiming more space than
+/// their drive has.
+pub fn steam_apps_strict() -> SteamResult<Vec<App>> {
+    scan_apps(true, &mut |_| true)
+}
+
+/// Like [`steam_apps`] but only returns apps that Steam has flagged as needing
+/// an update.
+pub fn steam_apps_update_pending() -> SteamResult<Vec<App>> {
+    filter_apps(&mut |state| {
+        state["StateFlags"].as_int().unwrap_or(0) as u32 & AppUpdateState::UPDATE_REQUIRED != 0
+    })
+}
+
+
+/// Like [`steam_apps`] but only returns apps taking more than `min_bytes`,
+/// checked before building the rest of the `App`.
+pub fn steam_apps_larger_than(min_bytes: u64) -> SteamResult<Vec<App>> {
+    filter_apps(&mut |state| {
+        state["SizeOnDisk"].as_int().is_some_and(|size| size > 0 && size as u64 > min_bytes)
+    })
+}
+
+/// Like [`steam_apps`] but leaves out DLC, recognized by being listed in the
+/// `DlcData` section or a depot's `dlcappid` of another installed app.
+pub fn steam_apps_base_only() -> SteamResult<Vec<App>> {
+    let mut dlc = Vec::<AppId>::new();
+    let mut apps = filter_apps(&mut |state| {
+        dlc.extend(state["DlcData"].iter().filter_map(|(app_id, _)| app_id.parse().ok().map(AppId)));
+        dlc.extend(state["InstalledDepots"]
+            .iter()
+            .filter_map(|(_, depot)| depot["dlcappid"].as_int())
+            .map(|app_id| AppId(app_id as u64)));
+        true
+    })?;
+    apps.retain(|app| !dlc.contains(&app.app_id));
+    Ok(apps)
+}
+
+/// Like [`steam_apps`] but with symlinks in each `path` resolved to the
+/// physical install location. Paths that fail to resolve are kept as is.
+pub fn steam_apps_resolve_symlinks() -> SteamResult<Vec<App>> {
+    let mut apps = steam_apps()?;
+    for app in &mut apps {
+        if let Ok(path) = fs::canonicalize(&app.path) {
+            app.path = path;
+        }
+    }
+    Ok(apps)
+}
+
+/// Like [`steam_apps`] but passes errors from individual libraries to
+/// `on_error` and carries on with the remaining libraries.
+pub fn steam_apps_with_error_handler(mut on_error: impl FnMut(LibraryError)) -> SteamResult<Vec<App>> {
+    let mut apps = Vec::new();
+    for path in steam_libraries()? {
+        match scan_library(&path, false, &mut |_| true) {
+            Ok(library) => apps.extend(library),
+            Err(error) => on_error(LibraryError { path, error }),
         }
     }
-    Err(io::Error::new(io::ErrorKind::NotFound, "failed to find app"))
+    dedup_apps(&mut apps);
+    Ok(apps)
+}
+
+/// Like [`steam_apps_with_error_handler`] but returns the libraries that
+/// failed alongside the apps from the rest.
+pub fn steam_apps_partial() -> SteamResult<(Vec<App>, Vec<LibraryError>)> {
+    let mut errors = Vec::new();
+    let apps = steam_apps_with_error_handler(|err| errors.push(err))?;
+    Ok((apps, errors))
 }
 
+/// Error from scanning a single library's `steamapps/` directory.
 #[derive(Debug)]
-pub struct App {
-    pub app_id: u64,
-    pub name: String,
-    pub size_on_disk: u64,
+pub struct LibraryError {
     pub path: PathBuf,
+    pub error: SteamError,
 }
 
-#[derive(Debug)]
-enum Value<'a> {
-    Map(Vec<(Cow<'a, str>, Value<'a>)>),
-    Str(Cow<'a, str>),
-    Null,
+impl std::fmt::Display for LibraryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.path.display(), self.error)
+    }
 }
 
-impl<'a> Value<'a> {
-    fn as_str(&self) -> Option<&str> {
-        match self {
-            Value::Str(s) => Some(s.as_ref()),
-            _ => None,
-        }
+impl std::error::Error for LibraryError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.error)
     }
+}
 
-    fn as_int(&self) -> Option<i64> {
-        match self {
-            Value::Str(s) => i64::from_str_radix(s, 10).ok(),
-            _ => None,
+/// Like [`steam_apps`] but scans each library on its own thread.
+pub fn steam_apps_parallel() -> SteamResult<Vec<App>> {
+    let libraries = steam_libraries()?;
+    let mut apps = std::thread::scope(|scope| {
+        let handles = libraries
+            .iter()
+            .map(|path| scope.spawn(|| library_apps(path, |_| true).filter_map(|app| app.ok()).collect::<Vec<_>>()))
+            .collect::<Vec<_>>();
+
+        let mut apps = Vec::new();
+        for handle in handles {
+            apps.extend(handle.join().unwrap());
         }
+        apps
+    });
+    dedup_apps(&mut apps);
+    Ok(apps)
+}
+
+/// Apps in any `steamapps/` directory, whether or not it is registered with
+/// the local Steam install, sorted by app ID. `libraryfolders.vdf` is not
+/// read.
+pub fn parse_steamapps_directory(path: &Path) -> SteamResult<Vec<App>> {
+    let mut apps = scan_library(path, false, &mut |_| true)?;
+    apps.sort_unstable_by_key(|app| app.app_id);
+    Ok(apps)
+}
+
+/// Same as [`parse_steamapps_directory`].
+pub fn steam_apps_in_library(steamapps_path: &Path) -> SteamResult<Vec<App>> {
+    parse_steamapps_directory(steamapps_path)
+}
+
+/// Path of `steamapps/libraryfolders.vdf`, which lists every library.
+pub fn libraryfolders_vdf_path() -> SteamResult<PathBuf> {
+    let mut path = steam_dir()?;
+    path.push("steamapps");
+    path.push("libraryfolders.vdf");
+    Ok(path)
+}
+
+/// `steamapps/` directory of every library listed in `libraryfolders.vdf`,
+/// plus the default one next to [`steam_dir`].
+pub fn steam_libraries() -> SteamResult<Vec<PathBuf>> {
+    library_paths(&libraryfolders_vdf()?)
+}
+
+// `steam_libraries` for an already parsed `libraryfolders.vdf`
+fn library_paths(lib: &VdfValue) -> SteamResult<Vec<PathBuf>> {
+    let mut libraries = parse_library_paths(lib);
+    let default = steam_dir()?.join("steamapps");
+    // `steam_dir` can spell the default library differently from
+    // `libraryfolders.vdf`, e.g. through a symlink or a lowercased registry path
+    let default_real = real_path(&default);
+    if default.is_dir() && !libraries.iter().any(|path| real_path(path) == default_real) {
+        libraries.insert(0, default);
     }
+    Ok(libraries)
+}
 
-    fn iter(&self) -> std::slice::Iter<'a, (Cow<'a, str>, Value<'_>)> {
-        match self {
-            Value::Map(map) => map.iter(),
-            _ => [].iter(),
+// `path` with symlinks resolved, or as is if that fails
+fn real_path(path: &Path) -> PathBuf {
+    fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// The parsed `libraryfolders.vdf`, for fields this crate has no accessor for.
+pub fn libraryfolders_vdf() -> SteamResult<VdfValue<'static>> {
+    read_vdf(&libraryfolders_vdf_path()?)
+}
+
+// text or binary VDF, telling them apart by the binary format's leading
+// map type byte
+fn read_vdf(path: &Path) -> SteamResult<VdfValue<'static>> {
+    let buffer = fs::read(path).map_err(with_path(path))?;
+    if buffer.first() == Some(&0) {
+        return vdf_parse_binary(&buffer).map_err(|err| SteamError::parse(path, err));
+    }
+    let buffer = String::from_utf8(buffer).map_err(|err| SteamError::parse(path, err))?;
+    let ast = vdf_parse(&buffer).map_err(|err| SteamError::parse(path, err))?;
+    Ok(ast.into_owned())
+}
+
+// libraries are keyed "0", "1", ... next to bookkeeping keys such as
+// `contentstatsid`
+fn library_entries<'v, 'a>(lib: &'v VdfValue<'a>) -> impl Iterator<Item = &'v VdfValue<'a>> {
+    lib["libraryfolders"]
+        .iter()
+        .filter(|(key, _)| !key.is_empty() && key.bytes().all(|b| b.is_ascii_digit()))
+        .map(|(_, map)| map)
+}
+
+// paths in `libraryfolders.vdf` are UTF-8 on every platform, including
+// non-ASCII Windows paths, so they can be used as `Path`s directly
+fn parse_library_paths(lib: &VdfValue) -> Vec<PathBuf> {
+    let mut libraries = Vec::new();
+    for map in library_entries(lib) {
+        if let Some(path) = map["path"].as_str() {
+            let path = Path::new(&path).join("steamapps");
+            libraries.push(path);
         }
     }
+    libraries
 }
 
-impl<'a> std::ops::Index<&str> for Value<'a> {
-    type Output = Value<'a>;
+fn scan_apps(strict: bool, filter: &mut dyn FnMut(&VdfValue) -> bool) -> SteamResult<Vec<App>> {
+    let mut apps = Vec::new();
+    for path in steam_libraries()?.iter() {
+        apps.extend(scan_library(path, strict, filter)?);
+    }
+    dedup_apps(&mut apps);
+    Ok(apps)
+}
 
-    fn index(&self, key: &str) -> &Self::Output {
-        match self {
-            Value::Map(map) => map
-                .iter()
-                .find(|(probe, _)| probe.eq_ignore_ascii_case(key))
-                .map(|res| &res.1)
-                .unwrap_or(&Value::Null),
-            _ => &Value::Null,
+fn read_library(path: &Path) -> SteamResult<fs::ReadDir> {
+    fs::read_dir(path).map_err(|err| match err.kind() {
+        io::ErrorKind::NotFound => SteamError::LibraryNotFound(path.to_path_buf()),
+        _ => with_path(path)(err).into(),
+    })
+}
+
+// `filter` is checked against the `AppState` block before an `App` is built
+fn scan_library(
+    path: &Path,
+    strict: bool,
+    filter: &mut dyn FnMut(&VdfValue) -> bool,
+) -> SteamResult<Vec<App>> {
+    let mut apps = Vec::new();
+    let root = path.join("common");
+    let has_common = root.is_dir();
+    for fd in read_library(path)? {
+        apps.extend(read_manifest(&fd?.path(), &root, has_common, strict, filter)?);
+    }
+    Ok(apps)
+}
+
+// `Ok(None)` for files that are not app manifests or that `filter` rejects,
+// and in strict mode for apps claiming more space than their drive has
+fn read_manifest(
+    path: &Path,
+    root: &Path,
+    has_common: bool,
+    strict: bool,
+    filter: &mut dyn FnMut(&VdfValue) -> bool,
+) -> SteamResult<Option<App>> {
+    if path.extension().and_then(|os| os.to_str()) != Some("acf") {
+        return Ok(None);
+    }
+    let buffer = fs::read_to_string(path).map_err(with_path(path))?;
+    let ast = VdfParserOptions::new()
+        .lenient(!strict)
+        .parse(&buffer)
+        .map_err(|err| SteamError::parse(path, err))?;
+    let state = &ast["AppState"];
+    if !filter(state) {
+        return Ok(None);
+    }
+
+    let Some(app) = (|| {
+        Some(App {
+            app_id: AppId(state["appid"].as_int()? as u64),
+            size_on_disk: state["SizeOnDisk"].as_int().unwrap_or(0) as u64,
+            path: root.join(installdir(state)?),
+            library_path: path.parent().unwrap_or(path).to_path_buf(),
+            installdir: installdir(state)?.to_string(),
+            name: state["name"].as_str().unwrap_or("").to_string(),
+            last_updated: state["LastUpdated"].as_int().filter(|&t| t > 0).map(|t| t as u64),
+            auto_update_paused: state["AutoUpdatePaused"].as_int().unwrap_or(0) != 0,
+            scheduled_auto_update_time: state["ScheduledAutoUpdate"].as_int().filter(|&t| t > 0).map(|t| t as u64),
+            installed_depots: parse_depots(state),
+            state_flags: state["StateFlags"].as_int().unwrap_or(0) as u32,
+            build_id: state["buildid"].as_int().unwrap_or(0) as u64,
+            acf_path: path.to_path_buf(),
+            last_owner: state["LastOwner"].as_int().filter(|&id| id > 0).map(|id| id as u64),
+            app_type: AppType::parse(state["type"].as_str().unwrap_or("")),
+        })
+    })() else {
+        return Ok(None);
+    };
+    if manifest_app_id(path).is_some_and(|app_id| app_id != app.app_id) {
+        if strict {
+            return Err(SteamError::parse(path, "appid does not match file name"));
         }
+        return Ok(None);
+    }
+    if strict && !has_common {
+        return Err(SteamError::LibraryNotFound(root.to_path_buf()));
     }
+    // lenient mode leaves this to `App::validate`
+    if strict && drive_capacity(&app.library_path).is_some_and(|capacity| app.size_on_disk > capacity) {
+        return Ok(None);
+    }
+    if strict && !app.path.exists() && !is_plain_dir_name(state["installdir"].as_str()) {
+        return Err(SteamError::parse(path, "suspicious installdir"));
+    }
+    Ok(Some(app))
 }
 
-fn vdf_parse<'a>(mut stream: Chars<'a>) -> io::Result<Value<'a>> {
-    fn parse_str<'a>(chars: &mut Chars<'a>) -> io::Result<Cow<'a, str>> {
-        let buf = chars.as_str();
-        let mut len = 0;
-        let mut owned = None;
-        let mut is_escaped = false;
-        while let Some(next) = chars.next() {
-            if is_escaped {
-                is_escaped = false;
-                let owned = owned.get_or_insert(buf[..len].to_string());
-                match next {
-                    '"' => owned.push('"'),
-                    'r' => owned.push('\r'),
-                    'n' => owned.push('\n'),
-                    '\\' => owned.push('\\'),
-                    _ => unimplemented!(),
-                }
-            } else {
-                match next {
-                    '"' => break,
-                    '\\' => is_escaped = true,
-                    _ => {
-                        if let Some(owned) = &mut owned {
-                            owned.push(next);
-                        } else {
-                            len += next.len_utf8();
-                        }
-                    }
-                }
-            }
+// total size in bytes of the drive holding `path`
+#[cfg(target_os = "windows")]
+fn drive_capacity(path: &Path) -> Option<u64> {
+    use std::os::windows::ffi::OsStrExt;
+
+    #[link(name = "kernel32.dll", kind = "raw-dylib", modifiers = "+verbatim")]
+    extern "system" {
+        fn GetDiskFreeSpaceExW(
+            lpdirectoryname: *const u16,
+            lpfreebytesavailabletocaller: *mut u64,
+            lptotalnumberofbytes: *mut u64,
+            lptotalnumberoffreebytes: *mut u64,
+        ) -> i32;
+    }
+
+    let path = path.as_os_str().encode_wide().chain([0]).collect::<Vec<_>>();
+    let mut total = 0;
+    unsafe {
+        if GetDiskFreeSpaceExW(path.as_ptr(), std::ptr::null_mut(), &mut total, std::ptr::null_mut()) != 0 {
+            return Some(total);
         }
-        Ok(if let Some(owned) = owned {
-            Cow::Owned(owned)
-        } else {
-            Cow::Borrowed(&buf[..len])
-        })
     }
+    None
+}
 
-    let mut stack: Vec<(Vec<(Cow<'a, str>, Value)>, Cow<'a, str>)> = Vec::with_capacity(16);
-    let mut map = Vec::new();
-    let mut key = None;
-    while let Some(start) = stream.next() {
-        if start.is_ascii_whitespace() {
-            continue;
+#[cfg(any(all(target_os = "linux", target_pointer_width = "64"), target_os = "macos"))]
+fn drive_capacity(path: &Path) -> Option<u64> {
+    use std::ffi::c_char;
+    use std::ffi::c_int;
+    use std::os::unix::ffi::OsStrExt;
+
+    // leading fields of `struct statvfs`, padded past the end of the rest
+    #[repr(C)]
+    struct StatVfs {
+        f_bsize: u64,
+        f_frsize: u64,
+        #[cfg(target_os = "linux")]
+        f_blocks: u64,
+        #[cfg(target_os = "macos")]
+        f_blocks: u32,
+        _rest: [u64; 16],
+    }
+
+    extern "C" {
+        fn statvfs(path: *const c_char, buf: *mut StatVfs) -> c_int;
+    }
+
+    let path = std::ffi::CString::new(path.as_os_str().as_bytes()).ok()?;
+    let mut buf = StatVfs {
+        f_bsize: 0,
+        f_frsize: 0,
+        f_blocks: 0,
+        _rest: [0; 16],
+    };
+    unsafe {
+        if statvfs(path.as_ptr(), &mut buf) != 0 {
+            return None;
         }
+    }
+    // `fsblkcnt_t` is only 32 bits on macOS
+    #[allow(clippy::unnecessary_cast)]
+    (buf.f_blocks as u64).checked_mul(buf.f_frsize)
+}
+
+#[cfg(not(any(
+    target_os = "windows",
+    all(target_os = "linux", target_pointer_width = "64"),
+    target_os = "macos",
+)))]
+fn drive_capacity(_path: &Path) -> Option<u64> {
+    None
+}
 
-        if key.is_none() {
-            if start == '"' {
-                key = Some(parse_str(&mut stream).unwrap());
-            } else if start == '}' {
-                let (mut parent, key) = stack.pop().unwrap();
-                parent.push((key, Value::Map(map)));
-                map = parent;
-            } else {
-                return Err(io::Error::new(io::ErrorKind::InvalidData, "unexpected token while parsing"));
-            }
-        } else if let Some(key) = key.take() {
-            if start == '"' {
-                map.push((key, Value::Str(parse_str(&mut stream).unwrap())));
-            } else if start == '{' {
-                map.sort_unstable_by(|a, b| a.0.cmp(&b.0));
-                stack.push((std::mem::take(&mut map), key));
-            } else {
-                return Err(io::Error::new(io::ErrorKind::InvalidData, "unexpected token while parsing"));
+/// Like [`steam_apps`] but keeps one entry per app name, preferring the one
+/// with the highest build ID and then the most recently updated one. Apps
+/// without a name are all kept.
+pub fn steam_apps_deduplicate_by_name() -> SteamResult<Vec<App>> {
+    Ok(dedup_by_name(steam_apps()?))
+}
+
+fn dedup_by_name(apps: Vec<App>) -> Vec<App> {
+    let (mut apps, nameless): (Vec<_>, Vec<_>) = apps.into_iter().partition(|app| !app.name.is_empty());
+    apps.sort_by(|a, b| {
+        a.name
+            .cmp(&b.name)
+            .then(b.build_id.cmp(&a.build_id))
+            .then(b.last_updated.cmp(&a.last_updated))
+    });
+    apps.dedup_by(|a, b| a.name == b.name);
+    apps.extend(nameless);
+    apps.sort_unstable_by_key(|app| app.app_id);
+    apps
+}
+
+// an empty `installdir` would point the app at `common/` itself
+fn installdir<'v>(state: &'v VdfValue) -> Option<&'v str> {
+    state["installdir"].as_str().filter(|dir| !dir.trim().is_empty())
+}
+
+/// Like [`steam_apps`] but skips apps with an ID below `min_id` without
+/// reading their manifests.
+pub fn steam_apps_with_min_id(min_id: impl Into<AppId>) -> SteamResult<Vec<App>> {
+    let min_id = min_id.into();
+    let mut apps = Vec::new();
+    for library in steam_libraries()? {
+        let Ok(entries) = read_library(&library) else {
+            continue;
+        };
+        // the app ID is in the manifest's file name
+        for fd in entries.flatten() {
+            let Some(app_id) = manifest_app_id(&fd.path()).filter(|&app_id| app_id >= min_id) else {
+                continue;
+            };
+            if let Ok(Some(app)) = read_app_manifest(&library, app_id) {
+                apps.push(app);
             }
-        } else {
-            unreachable!();
         }
     }
-    map.sort_unstable_by(|a, b| a.0.cmp(&b.0));
-    Ok(Value::Map(map))
+    dedup_apps(&mut apps);
+    Ok(apps)
 }
 
+/// Like [`steam_apps`] but only keeps apps matching `filter`.
+pub fn steam_apps_filter(filter: AppFilter) -> SteamResult<Vec<App>> {
+    let mut apps = steam_apps_iter()?
+        .filter_map(|app| app.ok())
+        .filter(|app| filter.matches(app))
+        .collect::<Vec<_>>();
+    dedup_apps(&mut apps);
+    Ok(apps)
+}
 
+/// A predicate over [`App`]s for [`steam_apps_filter`], combined with
+/// [`AppFilter::and`], [`AppFilter::or`] and `!`.
+pub struct AppFilter(Box<dyn Fn(&App) -> bool>);
 
-#[cfg(test)]
-mod test {
-    #[test]
-    fn parse() {
-        let ast = crate::vdf_parse(r#"
-            "AppState"
-            {
-                "appid"     "55500"
-                "name"      "Test Game"
-                "UserConfig"
-                {
-                    "language"      "english"
-                }
-            }
-        "#.chars()).unwrap();
+impl AppFilter {
+    pub fn new(pred: impl Fn(&App) -> bool + 'static) -> AppFilter {
+        AppFilter(Box::new(pred))
+    }
 
-        assert_eq!(ast["AppState"]["appid"].as_int(), Some(55500));
-        assert_eq!(ast["AppState"]["name"].as_str(), Some("Test Game"));
-        assert_eq!(ast["AppState"]["UserConfig"]["language"].as_str(), Some("english"));
+    /// Name contains `pattern`, ignoring case.
+    pub fn by_name(pattern: &str) -> AppFilter {
+        let pattern = pattern.to_lowercase();
+        AppFilter::new(move |app| app.name.to_lowercase().contains(&pattern))
     }
 
-    #[test]
-    fn utf8() {
-        crate::vdf_parse(r#"
-            "ᚠ" {}
-        "#.chars()).unwrap();
+    pub fn by_min_size(bytes: u64) -> AppFilter {
+        AppFilter::new(move |app| app.size_on_disk >= bytes)
     }
 
-    #[test]
-    fn escaped_characters() {
-        let ast = crate::vdf_parse(r#"
-            "AppState" { "name" "\\" }
-        "#.chars()).unwrap();
-        assert_eq!(Some(r"\"), ast["appstate"]["name"].as_str(), "{ast:?}");
+    pub fn installed() -> AppFilter {
+        AppFilter::new(App::is_fully_installed)
     }
-}
\ No newline at end of file
+
+    pub fn update_required() -> AppFilter {
+        AppFilter::new(|app| app.state_flags & AppUpdateState::UPDATE_REQUIRED != 0)
+    }
+
+    pub fn and(self, other: AppFilter) -> AppFilter {
+        AppFilter::new(move |app| self.matches(app) && other.matches(app))
+    }
+
+    pub fn or(self, other: AppFilter) -> AppFilter {
+        AppFilter::new(move |app| self.matches(app) || other.matches(app))
+    }
+
+    pub fn matches(&self, app: &App) -> bool {
+        (self.0)(app)
+    }
+}
+
+impl std::ops::Not for AppFilter {
+    type Output = AppFilter;
+
+    fn not(self) -> AppFilter {
+        AppFilter::new(move |app| !self.matches(app))
+    }
+}
+
+/// Installed apps whose name contains `query`, ignoring case.
+pub fn find_apps_by_name(query: &str) -> SteamResult<Vec<App>> {
+    let query = query.to_lowercase();
+    let mut apps = steam_apps()?;
+    apps.retain(|app| app.name.to_lowercase().contains(&query));
+    Ok(apps)
+}
+
+// app ID from an `appmanifest_{app_id}.acf` file name
+fn manifest_app_id(path: &Path) -> Option<AppId> {
+    path.file_stem()?
+        .to_str()?
+        .strip_prefix("appmanifest_")?
+        .parse()
+        .ok()
+        .map(AppId)
+}
+
+// `installdir` should name a single directory under `common/`
+fn is_plain_dir_name(installdir: Option<&str>) -> bool {
+    let mut components = match installdir {
+        Some(dir) => Path::new(dir).components(),
+        None => return false,
+    };
+    matches!(components.next(), Some(std::path::Component::Normal(_)))
+        && components.next().is_none()
+}
+
+pub fn get_steam_app(app_id: impl Into<AppId>) -> SteamResult<App> {
+    let app_id = app_id.into();
+    // the manifest name follows from the app ID, so there is no need to
+    // consult the `apps` lists, which can also be stale after moving a library
+    for library in steam_libraries()? {
+        if let Some(app) = read_app_manifest(&library, app_id)? {
+            return Ok(app);
+        }
+    }
+    Err(io::Error::new(io::ErrorKind::NotFound, "failed to find app").into())
+}
+
+// whether a `libraryfolders.vdf` entry lists `app_id` under `apps`
+fn lists_app(library: &VdfValue, app_id: AppId) -> bool {
+    library["apps"].iter().any(|(key, _)| key.parse().ok().map(AppId) == Some(app_id))
+}
+
+// `appmanifest_{app_id}.acf` in `library`, if there is one
+fn read_app_manifest(library: &Path, app_id: AppId) -> SteamResult<Option<App>> {
+    let manifest = library.join(format!("appmanifest_{app_id}.acf"));
+    if !manifest.is_file() {
+        return Ok(None);
+    }
+    let root = library.join("common");
+    read_manifest(&manifest, &root, root.is_dir(), false, &mut |_| true)
+}
+
+/// The `steamapps/` directory of the library holding `app_id`, going only by
+/// `libraryfolders.vdf`.
+pub fn library_folder_for_app(app_id: impl Into<AppId>) -> SteamResult<PathBuf> {
+    let app_id = app_id.into();
+    let lib = libraryfolders_vdf()?;
+    let library = library_entries(&lib)
+        .find(|map| lists_app(map, app_id))
+        .and_then(|map| map["path"].as_str())
+        .map(|path| Path::new(path).join("steamapps"));
+    library.ok_or(io::Error::new(io::ErrorKind::NotFound, "failed to find app").into())
+}
+
+/// Depots of `app_id` from its manifest's `InstalledDepots` section.
+pub fn depot_info(app_id: impl Into<AppId>) -> SteamResult<Vec<DepotInfo>> {
+    Ok(get_steam_app(app_id)?.installed_depots)
+}
+
+/// Like [`get_steam_app`] for several apps at once, reading
+/// `libraryfolders.vdf` a single time. Apps that are not installed or whose
+/// manifest can't be read are left out.
+pub fn get_steam_apps<I: Into<AppId> + Copy>(ids: &[I]) -> SteamResult<Vec<App>> {
+    let mut ids = ids.iter().map(|&app_id| app_id.into()).collect::<Vec<AppId>>();
+    ids.sort_unstable();
+    ids.dedup();
+    let libraries = steam_libraries()?;
+    // probe for each manifest like `get_steam_app` does, the `apps` lists can
+    // be stale after moving a library
+    let apps = ids
+        .into_iter()
+        .filter_map(|app_id| {
+            libraries
+                .iter()
+                .find_map(|library| read_app_manifest(library, app_id).ok().flatten())
+        })
+        .collect();
+    Ok(apps)
+}
+
+/// Wine prefix Proton made for `app` under `steamapps/compatdata/`. `None`
+/// for native games and before the first launch. Linux only.
+#[cfg(target_os = "linux")]
+pub fn proton_prefix_path(app: &App) -> Option<PathBuf> {
+    let mut path = app.library_path.join("compatdata");
+    path.push(app.app_id.to_string());
+    path.push("pfx");
+    path.is_dir().then_some(path)
+}
+
+/// The app installed at `path`, e.g. `steamapps/common/Team Fortress 2`.
+pub fn find_steam_app_by_install_path(path: &Path) -> SteamResult<App> {
+    let target = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    steam_apps()?
+        .into_iter()
+        .find(|app| app.path == path || fs::canonicalize(&app.path).is_ok_and(|path| path == target))
+        .ok_or(io::Error::new(io::ErrorKind::NotFound, "failed to find app").into())
+}
+
+pub fn find_game_header_image(app: &App) -> SteamResult<PathBuf> {
+    let mut path = steam_dir()?;
+    path.push("appcache");
+    path.push("librarycache");
+
+    let flat = path.join(format!("{}_library_600x900.jpg", app.app_id));
+    if flat.is_file() {
+        return Ok(flat);
+    }
+
+    // newer clients store library art in a directory per app
+    path.push(app.app_id.to_string());
+    path.push("library_600x900.jpg");
+    if path.is_file() {
+        return Ok(path);
+    }
+
+    Err(io::Error::new(io::ErrorKind::NotFound, "failed to find library image").into())
+}
+
+/// Steam app ID, kept apart from Steam IDs and depot IDs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(transparent))]
+pub struct AppId(pub u64);
+
+impl AppId {
+    pub const fn new(app_id: u64) -> Self {
+        AppId(app_id)
+    }
+}
+
+impl From<u64> for AppId {
+    fn from(app_id: u64) -> Self {
+        AppId(app_id)
+    }
+}
+
+impl From<AppId> for u64 {
+    fn from(app_id: AppId) -> Self {
+        app_id.0
+    }
+}
+
+impl std::fmt::Display for AppId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl std::str::FromStr for AppId {
+    type Err = std::num::ParseIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.parse().map(AppId)
+    }
+}
+
+/// An installed app, read from its `appmanifest_{app_id}.acf`.
+///
+/// Equality and hashing only look at `app_id`: two values with the same ID
+/// are the same game even if, say, their paths differ between machines.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct App {
+    pub app_id: AppId,
+    pub name: String,
+    pub size_on_disk: u64,
+    /// `steamapps/common/{installdir}`, which doesn't have to exist, e.g. for
+    /// a staging library without a `common/` directory.
+    pub path: PathBuf,
+    /// The `steamapps/` directory holding the manifest, as listed by
+    /// [`steam_libraries`].
+    pub library_path: PathBuf,
+    /// Name of the install directory under `steamapps/common/`.
+    pub installdir: String,
+    pub last_updated: Option<u64>,
+    pub auto_update_paused: bool,
+    pub scheduled_auto_update_time: Option<u64>,
+    pub installed_depots: Vec<DepotInfo>,
+    /// Raw `StateFlags` bitmask from the manifest.
+    pub state_flags: u32,
+    /// `buildid` of the installed content, `0` when missing.
+    pub build_id: u64,
+    /// The `appmanifest_{app_id}.acf` the app was read from.
+    pub acf_path: PathBuf,
+    /// SteamID64 of the account that last played the app.
+    pub last_owner: Option<u64>,
+    pub app_type: AppType,
+}
+
+/// The manifest's `type`, telling games apart from tools and runtimes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum AppType {
+    Game,
+    Tool,
+    Config,
+    Dlc,
+    Demo,
+    Application,
+    /// Any other value, empty when the manifest has no `type`.
+    Other(String),
+}
+
+impl AppType {
+    fn parse(s: &str) -> AppType {
+        match s.to_ascii_lowercase().as_str() {
+            "game" => AppType::Game,
+            "tool" => AppType::Tool,
+            "config" => AppType::Config,
+            "dlc" => AppType::Dlc,
+            "demo" => AppType::Demo,
+            "application" => AppType::Application,
+            _ => AppType::Other(s.to_string()),
+        }
+    }
+}
+
+/// The most pressing state in an app's `StateFlags`, e.g. `FilesCorrupt`
+/// over `NeedsUpdate` over `FullyInstalled`.
+///
+/// The associated constants are the raw `StateFlags` bits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AppUpdateState {
+    FullyInstalled,
+    NeedsUpdate,
+    Downloading,
+    Verifying,
+    FilesCorrupt,
+    UpdatePaused,
+    /// Flags without any of the states above.
+    Unknown(u32),
+}
+
+impl AppUpdateState {
+    pub const UPDATE_REQUIRED: u32 = 1 << 1;
+    pub const FULLY_INSTALLED: u32 = 1 << 2;
+    pub const FILES_MISSING: u32 = 1 << 5;
+    pub const FILES_CORRUPT: u32 = 1 << 7;
+    pub const UPDATE_RUNNING: u32 = 1 << 8;
+    pub const UPDATE_PAUSED: u32 = 1 << 9;
+    pub const UPDATE_STARTED: u32 = 1 << 10;
+    pub const VALIDATING: u32 = 1 << 17;
+    pub const DOWNLOADING: u32 = 1 << 20;
+    pub const STAGING: u32 = 1 << 21;
+    pub const COMMITTING: u32 = 1 << 22;
+
+    /// Picks the state from a raw `StateFlags` value.
+    pub fn from_flags(flags: u32) -> AppUpdateState {
+        let has = |bits: u32| flags & bits != 0;
+        if has(Self::FILES_MISSING | Self::FILES_CORRUPT) {
+            AppUpdateState::FilesCorrupt
+        } else if has(Self::VALIDATING) {
+            AppUpdateState::Verifying
+        } else if has(Self::UPDATE_PAUSED) {
+            AppUpdateState::UpdatePaused
+        } else if has(Self::UPDATE_RUNNING | Self::UPDATE_STARTED | Self::DOWNLOADING | Self::STAGING | Self::COMMITTING) {
+            AppUpdateState::Downloading
+        } else if has(Self::UPDATE_REQUIRED) {
+            AppUpdateState::NeedsUpdate
+        } else if has(Self::FULLY_INSTALLED) {
+            AppUpdateState::FullyInstalled
+        } else {
+            AppUpdateState::Unknown(flags)
+        }
+    }
+}
+
+/// Download state of a partially installed or preloaded app.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AppInstaller {
+    pub bytes_downloaded: u64,
+    pub bytes_to_download: u64,
+    pub bytes_staged: u64,
+    pub bytes_to_stage: u64,
+}
+
+/// An installed depot from a manifest's `InstalledDepots` section.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DepotInfo {
+    pub depot_id: u64,
+    pub manifest_id: u64,
+    pub size: u64,
+}
+
+fn parse_depots(state: &VdfValue) -> Vec<DepotInfo> {
+    state["InstalledDepots"]
+        .iter()
+        .filter_map(|(depot_id, depot)| Some(DepotInfo {
+            depot_id: depot_id.parse().ok()?,
+            manifest_id: depot["manifest"].as_str()?.parse().ok()?,
+            size: depot["size"].as_int().unwrap_or(0) as u64,
+        }))
+        .collect()
+}
+
+impl App {
+    pub fn app_id_eq(&self, other_id: impl Into<AppId>) -> bool {
+        self.app_id == other_id.into()
+    }
+
+    /// The `installdir` from the manifest, e.g. `Team Fortress 2`.
+    pub fn relative_installdir(&self) -> &str {
+        &self.installdir
+    }
+
+    /// The name lowercased with runs of other ASCII characters replaced by a
+    /// single `-`, e.g. `team-fortress-2`. Non-ASCII characters are dropped.
+    pub fn name_slug(&self) -> String {
+        let mut slug = String::with_capacity(self.name.len());
+        for c in self.name.chars().filter(char::is_ascii) {
+            if c.is_ascii_alphanumeric() {
+                slug.push(c.to_ascii_lowercase());
+            } else if !slug.is_empty() && !slug.ends_with('-') {
+                slug.push('-');
+            }
+        }
+        if slug.ends_with('-') {
+            slug.pop();
+        }
+        slug
+    }
+
+    /// `STEAM_APP_*` variables describing the app, for
+    /// [`Command::envs`](std::process::Command::envs).
+    pub fn to_env_pairs(&self) -> Vec<(String, String)> {
+        vec![
+            ("STEAM_APP_ID".to_string(), self.app_id.to_string()),
+            ("STEAM_APP_NAME".to_string(), self.name.clone()),
+            ("STEAM_APP_PATH".to_string(), self.path.to_string_lossy().into_owned()),
+            ("STEAM_APP_SIZE_ON_DISK".to_string(), self.size_on_disk.to_string()),
+        ]
+    }
+
+    /// The `scripts/` directory at the root of the install, kept by Source
+    /// engine games among others.
+    pub fn scripts_path(&self) -> Option<PathBuf> {
+        let path = self.path.join("scripts");
+        path.is_dir().then_some(path)
+    }
+
+    /// Number of depots in `InstalledDepots`.
+    pub fn depot_count(&self) -> usize {
+        self.installed_depots.len()
+    }
+
+    /// Manifest ID of an installed depot as hex.
+    pub fn depot_hash(&self, depot_id: u64) -> Option<String> {
+        self.installed_depots
+            .iter()
+            .find(|depot| depot.depot_id == depot_id)
+            .map(|depot| format!("{:x}", depot.manifest_id))
+    }
+
+    /// Whether Steam considers the app playable, as opposed to still
+    /// downloading or never finished installing.
+    pub fn is_fully_installed(&self) -> bool {
+        self.state_flags & AppUpdateState::FULLY_INSTALLED != 0
+    }
+
+    /// What Steam is doing with the app according to `state_flags`.
+    pub fn update_state(&self) -> AppUpdateState {
+        AppUpdateState::from_flags(self.state_flags)
+    }
+
+    /// Checks the manifest fields for values a healthy install shouldn't have.
+    pub fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        let mut errors = Vec::new();
+        if !self.path.exists() {
+            errors.push(ValidationError::MissingPath);
+        }
+        if self.app_id.0 == 0 {
+            errors.push(ValidationError::ZeroAppId);
+        }
+        if self.name.is_empty() {
+            errors.push(ValidationError::EmptyName);
+        }
+        if self.size_on_disk == 0 {
+            errors.push(ValidationError::ZeroSize);
+        }
+        if drive_capacity(&self.library_path).is_some_and(|capacity| self.size_on_disk > capacity) {
+            errors.push(ValidationError::SizeExceedsDrive);
+        }
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Time elapsed since Steam last updated the app.
+    pub fn time_since_update(&self) -> Option<std::time::Duration> {
+        let updated = std::time::UNIX_EPOCH + std::time::Duration::from_secs(self.last_updated?);
+        std::time::SystemTime::now().duration_since(updated).ok()
+    }
+
+    /// Total size of the files currently in the install directory.
+    pub fn actual_disk_usage(&self) -> SteamResult<u64> {
+        fn visit(dir: &Path) -> io::Result<u64> {
+            let mut size = 0;
+            for fd in fs::read_dir(dir).map_err(with_path(dir))? {
+                let fd = fd?;
+                let kind = fd.file_type()?;
+                if kind.is_dir() {
+                    size += visit(&fd.path())?;
+                } else if kind.is_file() {
+                    size += fd.metadata()?.len();
+                }
+            }
+            Ok(size)
+        }
+
+        Ok(visit(&self.path)?)
+    }
+
+    /// Guesses the platform the installed build targets from the files in
+    /// the top two levels of the install directory.
+    pub fn platform(&self) -> Option<ExecutablePlatform> {
+        fn visit(dir: &Path, depth: usize, found: &mut [bool; 3]) {
+            let Ok(entries) = fs::read_dir(dir) else {
+                return;
+            };
+            for fd in entries.flatten() {
+                let path = fd.path();
+                let ext = path.extension().and_then(|ext| ext.to_str()).map(str::to_ascii_lowercase);
+                let Ok(kind) = fd.file_type() else {
+                    continue;
+                };
+                match ext.as_deref() {
+                    Some("app") if kind.is_dir() => found[2] = true,
+                    Some("exe") if kind.is_file() => found[0] = true,
+                    Some("sh") if kind.is_file() => found[1] = true,
+                    None | Some("x86") | Some("x86_64") if kind.is_file() => {
+                        let mut magic = [0; 4];
+                        let is_elf = fs::File::open(&path)
+                            .and_then(|mut file| io::Read::read_exact(&mut file, &mut magic))
+                            .is_ok_and(|()| magic == *b"\x7fELF");
+                        found[1] |= is_elf;
+                    }
+                    _ if kind.is_dir() && depth > 0 => visit(&path, depth - 1, found),
+                    _ => (),
+                }
+            }
+        }
+
+        let mut found = [false; 3];
+        visit(&self.path, 1, &mut found);
+        match found {
+            [true, false, false] => Some(ExecutablePlatform::Windows),
+            [false, true, false] => Some(ExecutablePlatform::Linux),
+            [false, false, true] => Some(ExecutablePlatform::Macos),
+            [false, false, false] => None,
+            _ => Some(ExecutablePlatform::MultiPlatform),
+        }
+    }
+
+    /// When the manifest was last written, for polling whether Steam changed
+    /// the app since it was read.
+    pub fn acf_modification_time(&self) -> SteamResult<std::time::SystemTime> {
+        let metadata = fs::metadata(&self.acf_path).map_err(with_path(&self.acf_path))?;
+        Ok(metadata.modified()?)
+    }
+
+    /// Progress of a pending download, read fresh from the manifest so
+    /// repeated calls follow it. `None` when nothing is queued or the
+    /// manifest can't be read.
+    pub fn installer_info(&self) -> Option<AppInstaller> {
+        let ast = read_vdf(&self.acf_path).ok()?;
+        let state = &ast["AppState"];
+        let bytes = |key| state[key].as_int().unwrap_or(0).max(0) as u64;
+        let installer = AppInstaller {
+            bytes_downloaded: bytes("BytesDownloaded"),
+            bytes_to_download: bytes("BytesToDownload"),
+            bytes_staged: bytes("BytesStaged"),
+            bytes_to_stage: bytes("BytesToStage"),
+        };
+        (installer.bytes_to_download > 0 || installer.bytes_to_stage > 0).then_some(installer)
+    }
+
+    /// Space freed by uninstalling, measured from disk when possible and
+    /// otherwise taken from the manifest.
+    pub fn uninstall_size_estimate(&self) -> u64 {
+        self.actual_disk_usage().unwrap_or(self.size_on_disk)
+    }
+
+    /// `size_on_disk` in the largest binary unit that keeps the integer part
+    /// non-zero, e.g. `"12.35 GiB"` for a `precision` of 2.
+    pub fn size_on_disk_human(&self, precision: usize) -> String {
+        const UNITS: [&str; 6] = ["B", "KiB", "MiB", "GiB", "TiB", "PiB"];
+        let mut size = self.size_on_disk as f64;
+        let mut unit = 0;
+        while size >= 1024.0 && unit < UNITS.len() - 1 {
+            size /= 1024.0;
+            unit += 1;
+        }
+        if unit == 0 {
+            format!("{} B", self.size_on_disk)
+        } else {
+            format!("{size:.precision$} {}", UNITS[unit])
+        }
+    }
+
+    /// Launch options the user set for this app, `None` if there are none.
+    ///
+    /// A `steam_id` of `0` uses the most recent user.
+    pub fn launch_options(&self, steam_id: u64) -> SteamResult<Option<String>> {
+        let steam_id = match steam_id {
+            0 => SteamUser::most_recent()?.steam_id,
+            id => id,
+        };
+        let options = steam_app_launch_options(steam_id, self.app_id)?;
+        Ok(Some(options).filter(|options| !options.is_empty()))
+    }
+
+    /// Files synced by Steam Cloud for this app.
+    ///
+    /// A `steam_id` of `0` uses the most recent user.
+    pub fn game_saves_steam_cloud(&self, steam_id: u64) -> SteamResult<Vec<PathBuf>> {
+        fn visit(dir: &Path, files: &mut Vec<PathBuf>) -> io::Result<()> {
+            for fd in fs::read_dir(dir).map_err(with_path(dir))? {
+                let fd = fd?;
+                if fd.file_type()?.is_dir() {
+                    visit(&fd.path(), files)?;
+                } else {
+                    files.push(fd.path());
+                }
+            }
+            Ok(())
+        }
+
+        let steam_id = match steam_id {
+            0 => SteamUser::most_recent()?.steam_id,
+            id => id,
+        };
+        let mut path = userdata_dir(steam_id)?;
+        path.push(self.app_id.to_string());
+        path.push("remote");
+        let mut files = Vec::new();
+        visit(&path, &mut files)?;
+        files.sort_unstable();
+        Ok(files)
+    }
+}
+
+/// An app derefs to its install directory.
+impl std::ops::Deref for App {
+    type Target = PathBuf;
+
+    fn deref(&self) -> &Self::Target {
+        &self.path
+    }
+}
+
+impl PartialEq for App {
+    fn eq(&self, other: &App) -> bool {
+        self.app_id == other.app_id
+    }
+}
+
+impl Eq for App {}
+
+impl std::hash::Hash for App {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.app_id.hash(state);
+    }
+}
+
+impl PartialEq<AppId> for App {
+    fn eq(&self, other: &AppId) -> bool {
+        self.app_id == *other
+    }
+}
+
+impl PartialEq<App> for AppId {
+    fn eq(&self, other: &App) -> bool {
+        *self == other.app_id
+    }
+}
+
+impl PartialEq<u64> for App {
+    fn eq(&self, other: &u64) -> bool {
+        self.app_id.0 == *other
+    }
+}
+
+impl PartialEq<App> for u64 {
+    fn eq(&self, other: &App) -> bool {
+        *self == other.app_id.0
+    }
+}
+
+/// Result of [`App::platform`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExecutablePlatform {
+    Windows,
+    Linux,
+    Macos,
+    MultiPlatform,
+}
+
+/// A problem found by [`App::validate`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValidationError {
+    /// The install directory doesn't exist.
+    MissingPath,
+    ZeroAppId,
+    EmptyName,
+    ZeroSize,
+    /// The size on disk is larger than the drive holding the install.
+    SizeExceedsDrive,
+}
+
+impl std::fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            ValidationError::MissingPath => "install directory does not exist",
+            ValidationError::ZeroAppId => "app ID is 0",
+            ValidationError::EmptyName => "name is empty",
+            ValidationError::ZeroSize => "size on disk is 0",
+            ValidationError::SizeExceedsDrive => "size on disk is larger than the drive",
+        })
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+impl From<App> for std::collections::HashMap<String, String> {
+    fn from(app: App) -> Self {
+        Self::from([
+            ("app_id".to_string(), app.app_id.to_string()),
+            ("name".to_string(), app.name),
+            ("size_on_disk".to_string(), app.size_on_disk.to_string()),
+            ("path".to_string(), app.path.to_string_lossy().into_owned()),
+        ])
+    }
+}
+
+#[derive(Debug)]
+pub struct SteamUser {
+    pub steam_id: u64,
+    pub account_name: String,
+    pub persona_name: String,
+    pub most_recent: bool,
+}
+
+impl SteamUser {
+    /// The account flagged as most recently logged in.
+    pub fn most_recent() -> SteamResult<SteamUser> {
+        steam_users()?
+            .into_iter()
+            .find(|user| user.most_recent)
+            .ok_or(io::Error::new(io::ErrorKind::NotFound, "failed to find recent user").into())
+    }
+
+    /// The user's Steam Community profile.
+    pub fn profile_url(&self) -> String {
+        format!("https://steamcommunity.com/profiles/{}", self.steam_id)
+    }
+
+    /// The 32-bit account ID that names the user's `userdata/` directory.
+    pub fn account_id(&self) -> u32 {
+        self.steam_id as u32
+    }
+
+    /// The cached avatar image, avoiding a network request.
+    pub fn avatar_path(&self) -> SteamResult<PathBuf> {
+        let steam_dir = steam_dir()?;
+        // current clients use the decimal SteamID64 under `config/`, older
+        // ones a hex ID under `appcache/`
+        let candidates = [
+            steam_dir.join("config").join("avatarcache").join(format!("{}.png", self.steam_id)),
+            steam_dir.join("appcache").join("avatarcache").join(format!("{:x}.png", self.steam_id)),
+        ];
+        candidates
+            .into_iter()
+            .find(|path| path.is_file())
+            .ok_or(io::Error::new(io::ErrorKind::NotFound, "failed to find avatar").into())
+    }
+
+    /// Previous persona names, newest first.
+    pub fn persona_name_history(&self) -> SteamResult<Vec<String>> {
+        let config = self.localconfig()?;
+        let account_id = self.account_id().to_string();
+        let history = &config["UserLocalConfigStore"]["friends"][account_id.as_str()]["NameHistory"];
+        Ok(history
+            .as_list()
+            .into_iter()
+            .filter_map(|name| name.as_str().map(str::to_string))
+            .collect())
+    }
+
+    /// App IDs listed in the user's `localconfig.vdf`, including DLC and
+    /// tools.
+    ///
+    /// Steam only updates this list when it syncs with the account so it may
+    /// lag behind recent purchases.
+    pub fn owned_app_ids(&self) -> SteamResult<Vec<AppId>> {
+        let config = self.localconfig()?;
+        let apps = localconfig_apps(&config);
+        let mut app_ids = apps
+            .iter()
+            .filter_map(|(app_id, _)| app_id.parse().ok().map(AppId))
+            .collect::<Vec<_>>();
+        app_ids.sort_unstable();
+        Ok(app_ids)
+    }
+
+    /// Total hours played for `app_id` as recorded in `localconfig.vdf`.
+    pub fn hours_played(&self, app_id: impl Into<AppId>) -> SteamResult<f64> {
+        let config = self.localconfig()?;
+        let apps = localconfig_apps(&config);
+        match &apps[app_id.into().to_string().as_str()] {
+            VdfValue::Null => Err(io::Error::new(io::ErrorKind::NotFound, "failed to find app").into()),
+            app => Ok(app["Playtime"].as_int().unwrap_or(0) as f64 / 60.0),
+        }
+    }
+
+    /// Non-Steam games the user added, from `config/shortcuts.vdf`.
+    pub fn shortcuts(&self) -> SteamResult<Vec<Shortcut>> {
+        let mut path = userdata_dir(self.steam_id)?;
+        path.push("config");
+        path.push("shortcuts.vdf");
+        Ok(parse_shortcuts(read_vdf(&path)?))
+    }
+
+    fn localconfig(&self) -> SteamResult<VdfValue<'static>> {
+        read_localconfig(self.steam_id)
+    }
+}
+
+fn read_localconfig(steam_id: u64) -> SteamResult<VdfValue<'static>> {
+    let mut path = userdata_dir(steam_id)?;
+    path.push("config");
+    path.push("localconfig.vdf");
+    read_vdf(&path)
+}
+
+/// Launch options `steam_id` set for `app_id`, empty if there are none.
+pub fn steam_app_launch_options(steam_id: u64, app_id: impl Into<AppId>) -> SteamResult<String> {
+    let localconfig = read_localconfig(steam_id)?;
+    let app_id = app_id.into().to_string();
+    let options = localconfig_apps(&localconfig)[app_id.as_str()]["LaunchOptions"].as_str();
+    Ok(options.unwrap_or("").to_string())
+}
+
+// per-app entries of a `localconfig.vdf`
+fn localconfig_apps<'v, 'a>(config: &'v VdfValue<'a>) -> &'v VdfValue<'a> {
+    &config["UserLocalConfigStore"]["Software"]["Valve"]["Steam"]["apps"]
+}
+
+fn parse_shortcuts(shortcuts: VdfValue<'static>) -> Vec<Shortcut> {
+    let VdfValue::Map(mut root) = shortcuts else {
+        return Vec::new();
+    };
+    let Some(index) = root.iter().position(|(key, _)| key.eq_ignore_ascii_case("shortcuts")) else {
+        return Vec::new();
+    };
+    let VdfValue::Map(entries) = root.swap_remove(index).1 else {
+        return Vec::new();
+    };
+    entries
+        .into_iter()
+        .map(|(_, entry)| Shortcut {
+            app_id: entry["appid"].as_int().unwrap_or(0) as u32,
+            name: entry["AppName"].as_str().unwrap_or("").to_string(),
+            exe: entry["Exe"].as_str().unwrap_or("").to_string(),
+            start_dir: entry["StartDir"].as_str().unwrap_or("").to_string(),
+            launch_options: entry["LaunchOptions"].as_str().unwrap_or("").to_string(),
+            entry,
+        })
+        .collect()
+}
+
+/// A non-Steam game added to the library.
+#[derive(Debug)]
+pub struct Shortcut {
+    /// ID Steam generated for the shortcut, not a store app ID.
+    pub app_id: u32,
+    pub name: String,
+    pub exe: String,
+    pub start_dir: String,
+    pub launch_options: String,
+    entry: VdfValue<'static>,
+}
+
+impl Shortcut {
+    /// User categories such as `Favorite`.
+    pub fn tags(&self) -> Vec<String> {
+        self.entry["tags"]
+            .as_list()
+            .into_iter()
+            .filter_map(|tag| tag.as_str().map(str::to_string))
+            .collect()
+    }
+
+    /// Whether the user hid the shortcut from their library.
+    pub fn hide_desktop_shortcut(&self) -> bool {
+        self.entry["IsHidden"].as_int().is_some_and(|hidden| hidden != 0)
+    }
+}
+
+/// An [`App`] with settings from the most recent user's `localconfig.vdf`
+/// and from `config/config.vdf`.
+#[derive(Debug)]
+pub struct AppWithMetadata {
+    pub app: App,
+    pub launch_options: Option<String>,
+    /// Name of the compatibility tool forced for the app, e.g. `proton_9`.
+    pub compat_tool: Option<String>,
+    pub hours_played: Option<f64>,
+    pub cloud_sync: bool,
+}
+
+/// Like [`steam_apps`] but with per-user metadata, reading each config file
+/// once for all apps.
+pub fn steam_apps_with_metadata() -> SteamResult<Vec<AppWithMetadata>> {
+    let apps = steam_apps()?;
+    let localconfig = SteamUser::most_recent()?.localconfig()?;
+    let local_apps = localconfig_apps(&localconfig);
+    // only holds compatibility tool overrides so a missing file is fine
+    let config = steam_config().unwrap_or(VdfValue::Null);
+    let compat_tools = &config["InstallConfigStore"]["Software"]["Valve"]["Steam"]["CompatToolMapping"];
+
+    Ok(apps
+        .into_iter()
+        .map(|app| {
+            let app_id = app.app_id.to_string();
+            let local = &local_apps[app_id.as_str()];
+            AppWithMetadata {
+                launch_options: local["LaunchOptions"]
+                    .as_str()
+                    .filter(|s| !s.is_empty())
+                    .map(str::to_string),
+                compat_tool: compat_tools[app_id.as_str()]["name"]
+                    .as_str()
+                    .filter(|s| !s.is_empty())
+                    .map(str::to_string),
+                hours_played: local["Playtime"].as_int().map(|minutes| minutes as f64 / 60.0),
+                cloud_sync: local["cloud"]["last_sync_state"].as_str() == Some("synchronized"),
+                app,
+            }
+        })
+        .collect())
+}
+
+/// The parsed `config/config.vdf`.
+///
+/// Everything lives under a single `InstallConfigStore` root. Client settings
+/// are in `InstallConfigStore/Software/Valve/Steam`, which holds sections such
+/// as `CompatToolMapping` (per-app compatibility tools) and `Accounts`.
+pub fn steam_config() -> SteamResult<VdfValue<'static>> {
+    let mut path = steam_dir()?;
+    path.push("config");
+    path.push("config.vdf");
+    read_vdf(&path)
+}
+
+// `userdata/` is keyed by the 32-bit account ID rather than the SteamID64
+fn userdata_dir(steam_id: u64) -> SteamResult<PathBuf> {
+    let mut path = steam_dir()?;
+    path.push("userdata");
+    path.push((steam_id as u32).to_string());
+    Ok(path)
+}
+
+/// Accounts that have logged in on this machine, from `config/loginusers.vdf`.
+pub fn steam_users() -> SteamResult<Vec<SteamUser>> {
+    let mut path = steam_dir()?;
+    path.push("config");
+    path.push("loginusers.vdf");
+    Ok(parse_users(&read_vdf(&path)?))
+}
+
+// entries are keyed by SteamID64, anything else is skipped
+fn parse_users(loginusers: &VdfValue) -> Vec<SteamUser> {
+    loginusers["users"]
+        .iter()
+        .filter_map(|(steam_id, user)| Some(SteamUser {
+            steam_id: steam_id.parse().ok()?,
+            account_name: user["AccountName"].as_str().unwrap_or_default().to_string(),
+            persona_name: user["PersonaName"].as_str().unwrap_or_default().to_string(),
+            most_recent: user["MostRecent"].as_int() == Some(1),
+        }))
+        .collect()
+}
+
+/// A parsed VDF node.
+///
+/// Strings borrow from the parsed input where possible.
+#[derive(Debug, PartialEq)]
+pub enum VdfValue<'a> {
+    Map(Vec<(Cow<'a, str>, VdfValue<'a>)>),
+    Str(Cow<'a, str>),
+    Null,
+}
+
+impl<'a> VdfValue<'a> {
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            VdfValue::Str(s) => Some(s.as_ref()),
+            _ => None,
+        }
+    }
+
+    pub fn as_int(&self) -> Option<i64> {
+        match self {
+            VdfValue::Str(s) => s.parse().ok(),
+            _ => None,
+        }
+    }
+
+    /// Whether this is a missing key, as opposed to an empty string.
+    pub fn is_null(&self) -> bool {
+        matches!(self, VdfValue::Null)
+    }
+
+    /// Returns `self`, panicking with `msg` for a missing key.
+    #[track_caller]
+    pub fn expect(&self, msg: &str) -> &VdfValue<'a> {
+        if self.is_null() {
+            panic!("{msg}");
+        }
+        self
+    }
+
+    pub fn is_str(&self) -> bool {
+        matches!(self, VdfValue::Str(_))
+    }
+
+    pub fn is_map(&self) -> bool {
+        matches!(self, VdfValue::Map(_))
+    }
+
+    pub fn into_owned(self) -> VdfValue<'static> {
+        match self {
+            VdfValue::Map(map) => VdfValue::Map(map
+                .into_iter()
+                .map(|(key, value)| (Cow::Owned(key.into_owned()), value.into_owned()))
+                .collect()),
+            VdfValue::Str(s) => VdfValue::Str(Cow::Owned(s.into_owned())),
+            VdfValue::Null => VdfValue::Null,
+        }
+    }
+
+    /// Entries of a map as a slice, or `None` for other values.
+    pub fn as_map(&self) -> Option<&[(Cow<'a, str>, VdfValue<'a>)]> {
+        match self {
+            VdfValue::Map(map) => Some(map),
+            _ => None,
+        }
+    }
+
+    /// Same as [`VdfValue::as_map`].
+    pub fn as_map_ref(&self) -> Option<&[(Cow<'a, str>, VdfValue<'a>)]> {
+        self.as_map()
+    }
+
+    /// Entries of a map, or nothing for other values.
+    pub fn iter(&self) -> std::slice::Iter<'_, (Cow<'a, str>, VdfValue<'a>)> {
+        match self {
+            VdfValue::Map(map) => map.iter(),
+            _ => [].iter(),
+        }
+    }
+
+    /// Number of entries in a map, or `0` for other values.
+    pub fn len(&self) -> usize {
+        self.iter().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Same as [`len`](Self::len), for code that walks the tree.
+    pub fn child_count(&self) -> usize {
+        self.len()
+    }
+
+    /// How many entries of a map use `key`, compared like indexing does.
+    pub fn count(&self, key: &str) -> usize {
+        self.iter().filter(|(probe, _)| probe.eq_ignore_ascii_case(key)).count()
+    }
+
+    /// The entry keyed by the decimal string of `index`, as used by list-like
+    /// maps (`"0"`, `"1"`, ...).
+    pub fn get_index(&self, index: usize) -> Option<&VdfValue<'a>> {
+        match &self[index.to_string().as_str()] {
+            VdfValue::Null => None,
+            value => Some(value),
+        }
+    }
+
+    /// Values keyed `"0"`, `"1"`, ... in order, up to the first missing index.
+    pub fn as_list(&self) -> Vec<&VdfValue<'a>> {
+        (0..).map_while(|i| self.get_index(i)).collect()
+    }
+
+    /// Every value in the tree, depth-first, whose key and value match `pred`.
+    pub fn find_all(&self, pred: impl Fn(&str, &VdfValue<'a>) -> bool) -> Vec<&VdfValue<'a>> {
+        fn visit<'v, 'a>(
+            value: &'v VdfValue<'a>,
+            pred: &dyn Fn(&str, &VdfValue<'a>) -> bool,
+            found: &mut Vec<&'v VdfValue<'a>>,
+        ) {
+            for (key, child) in value.iter() {
+                if pred(key, child) {
+                    found.push(child);
+                }
+                visit(child, pred, found);
+            }
+        }
+
+        let mut found = Vec::new();
+        visit(self, &pred, &mut found);
+        found
+    }
+
+    /// Calls `visitor` for every value in the tree, depth-first, with the keys
+    /// leading to it from `self`.
+    pub fn walk(&self, mut visitor: impl FnMut(&[&str], &VdfValue<'a>)) {
+        fn visit<'v, 'a>(
+            value: &'v VdfValue<'a>,
+            path: &mut Vec<&'v str>,
+            visitor: &mut dyn FnMut(&[&str], &VdfValue<'a>),
+        ) {
+            for (key, child) in value.iter() {
+                path.push(key);
+                visitor(path, child);
+                visit(child, path, visitor);
+                path.pop();
+            }
+        }
+
+        visit(self, &mut Vec::new(), &mut visitor);
+    }
+
+    /// A copy of the tree with every key replaced by `f(key)`.
+    pub fn map_keys(&self, f: impl Fn(&str) -> String) -> VdfValue<'a> {
+        fn map<'a>(value: &VdfValue<'a>, f: &dyn Fn(&str) -> String) -> VdfValue<'a> {
+            match value {
+                VdfValue::Map(entries) => VdfValue::Map(entries
+                    .iter()
+                    .map(|(key, value)| (Cow::Owned(f(key)), map(value, f)))
+                    .collect()),
+                VdfValue::Str(s) => VdfValue::Str(s.clone()),
+                VdfValue::Null => VdfValue::Null,
+            }
+        }
+
+        map(self, &f)
+    }
+}
+
+impl<'a> std::ops::Index<&str> for VdfValue<'a> {
+    type Output = VdfValue<'a>;
+
+    fn index(&self, key: &str) -> &Self::Output {
+        match self {
+            VdfValue::Map(map) => map
+                .iter()
+                .find(|(probe, _)| probe.eq_ignore_ascii_case(key))
+                .map(|res| &res.1)
+                .unwrap_or(&VdfValue::Null),
+            _ => &VdfValue::Null,
+        }
+    }
+}
+
+// quotes `s` with the escapes `vdf_parse` understands
+fn write_quoted(out: &mut impl Write, s: &str) -> std::fmt::Result {
+    out.write_char('"')?;
+    for c in s.chars() {
+        match c {
+            '"' => out.write_str("\\\"")?,
+            '\\' => out.write_str("\\\\")?,
+            '\n' => out.write_str("\\n")?,
+            '\r' => out.write_str("\\r")?,
+            _ => out.write_char(c)?,
+        }
+    }
+    out.write_char('"')
+}
+
+/// Builds VDF text incrementally.
+///
+/// [`pair`](Self::pair), [`begin`](Self::begin) and [`end`](Self::end) take
+/// care of quoting and indentation while the [`Write`](std::fmt::Write) impl
+/// appends raw text.
+#[derive(Debug, Default)]
+pub struct VdfBuilder {
+    buffer: String,
+    depth: usize,
+}
+
+impl VdfBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Writes a `"key" "value"` line.
+    pub fn pair(&mut self, key: &str, value: &str) -> &mut Self {
+        write!(self.buffer, "{:\t<1$}", "", self.depth).unwrap();
+        write_quoted(&mut self.buffer, key).unwrap();
+        self.buffer.push_str("\t\t");
+        write_quoted(&mut self.buffer, value).unwrap();
+        self.buffer.push('\n');
+        self
+    }
+
+    /// Opens a nested map under `key`.
+    pub fn begin(&mut self, key: &str) -> &mut Self {
+        write!(self.buffer, "{:\t<1$}", "", self.depth).unwrap();
+        write_quoted(&mut self.buffer, key).unwrap();
+        writeln!(self.buffer, "\n{:\t<1$}{{", "", self.depth).unwrap();
+        self.depth += 1;
+        self
+    }
+
+    /// Closes the innermost map opened with [`begin`](Self::begin).
+    pub fn end(&mut self) -> &mut Self {
+        self.depth = self.depth.saturating_sub(1);
+        writeln!(self.buffer, "{:\t<1$}}}", "", self.depth).unwrap();
+        self
+    }
+
+    pub fn finish(self) -> String {
+        self.buffer
+    }
+}
+
+impl Write for VdfBuilder {
+    fn write_str(&mut self, s: &str) -> std::fmt::Result {
+        self.buffer.push_str(s);
+        Ok(())
+    }
+}
+
+impl std::str::FromStr for VdfValue<'static> {
+    type Err = VdfParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(vdf_parse(s)?.into_owned())
+    }
+}
+
+/// Formats the value as VDF text, indented with tabs the way Steam writes it.
+impl std::fmt::Display for VdfValue<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        fn write_map(
+            f: &mut std::fmt::Formatter<'_>,
+            entries: &[(Cow<'_, str>, VdfValue<'_>)],
+            depth: usize,
+        ) -> std::fmt::Result {
+            for (key, value) in entries {
+                match value {
+                    VdfValue::Map(children) => {
+                        write!(f, "{:\t<depth$}", "")?;
+                        write_quoted(f, key)?;
+                        writeln!(f)?;
+                        writeln!(f, "{:\t<depth$}{{", "")?;
+                        write_map(f, children, depth + 1)?;
+                        writeln!(f, "{:\t<depth$}}}", "")?;
+                    }
+                    VdfValue::Str(s) => {
+                        write!(f, "{:\t<depth$}", "")?;
+                        write_quoted(f, key)?;
+                        f.write_str("\t\t")?;
+                        write_quoted(f, s)?;
+                        writeln!(f)?;
+                    }
+                    VdfValue::Null => (),
+                }
+            }
+            Ok(())
+        }
+
+        match self {
+            VdfValue::Map(entries) => write_map(f, entries, 0),
+            VdfValue::Str(s) => write_quoted(f, s),
+            VdfValue::Null => Ok(()),
+        }
+    }
+}
+
+// `serde::Deserializer` for `&VdfValue` so `T::deserialize(&value)` works.
+// VDF only has strings, so numbers and bools are parsed from them, and struct
+// fields match keys ignoring case like `VdfValue`'s `Index`.
+#[cfg(feature = "serde")]
+mod de {
+    use super::VdfParseError;
+    use super::VdfValue;
+    use serde::de;
+    use serde::de::value::BorrowedStrDeserializer;
+    use serde::de::IntoDeserializer;
+    use serde::de::Visitor;
+    use std::borrow::Cow;
+
+    impl de::Error for VdfParseError {
+        fn custom<T: std::fmt::Display>(msg: T) -> Self {
+            VdfParseError {
+                message: msg.to_string(),
+                key_path: Vec::new(),
+            }
+        }
+    }
+
+    impl<'de> VdfValue<'de> {
+        fn parsed<T: std::str::FromStr>(&self, expected: &str) -> Result<T, VdfParseError> {
+            self.as_str()
+                .and_then(|s| s.trim().parse().ok())
+                .ok_or_else(|| de::Error::custom(format!("expected {expected}")))
+        }
+    }
+
+    macro_rules! deserialize_parsed {
+        ($($method:ident => $visit:ident: $ty:ty,)*) => {$(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+                visitor.$visit(self.parsed::<$ty>(stringify!($ty))?)
+            }
+        )*};
+    }
+
+    impl<'de, 'a> de::Deserializer<'de> for &'de VdfValue<'a> {
+        type Error = VdfParseError;
+
+        fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+            match self {
+                VdfValue::Map(entries) => visitor.visit_map(MapAccess::new(entries, &[])),
+                // `self` lives for `'de` so even owned strings can be lent out
+                VdfValue::Str(s) => visitor.visit_borrowed_str(s),
+                VdfValue::Null => visitor.visit_unit(),
+            }
+        }
+
+        fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+            match self.as_str().map(str::trim) {
+                Some("1") => visitor.visit_bool(true),
+                Some("0") => visitor.visit_bool(false),
+                Some(s) if s.eq_ignore_ascii_case("true") => visitor.visit_bool(true),
+                Some(s) if s.eq_ignore_ascii_case("false") => visitor.visit_bool(false),
+                _ => Err(de::Error::custom("expected bool")),
+            }
+        }
+
+        deserialize_parsed! {
+            deserialize_i8 => visit_i8: i8,
+            deserialize_i16 => visit_i16: i16,
+            deserialize_i32 => visit_i32: i32,
+            deserialize_i64 => visit_i64: i64,
+            deserialize_u8 => visit_u8: u8,
+            deserialize_u16 => visit_u16: u16,
+            deserialize_u32 => visit_u32: u32,
+            deserialize_u64 => visit_u64: u64,
+            deserialize_f32 => visit_f32: f32,
+            deserialize_f64 => visit_f64: f64,
+            deserialize_char => visit_char: char,
+        }
+
+        fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+            match self {
+                VdfValue::Null => visitor.visit_none(),
+                _ => visitor.visit_some(self),
+            }
+        }
+
+        fn deserialize_newtype_struct<V: Visitor<'de>>(
+            self,
+            _name: &'static str,
+            visitor: V,
+        ) -> Result<V::Value, Self::Error> {
+            visitor.visit_newtype_struct(self)
+        }
+
+        // lists are maps keyed "0", "1", ...
+        fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+            match self {
+                VdfValue::Map(_) => visitor.visit_seq(SeqAccess(self.as_list().into_iter())),
+                _ => Err(de::Error::custom("expected map")),
+            }
+        }
+
+        fn deserialize_tuple<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value, Self::Error> {
+            self.deserialize_seq(visitor)
+        }
+
+        fn deserialize_tuple_struct<V: Visitor<'de>>(
+            self,
+            _name: &'static str,
+            _len: usize,
+            visitor: V,
+        ) -> Result<V::Value, Self::Error> {
+            self.deserialize_seq(visitor)
+        }
+
+        fn deserialize_struct<V: Visitor<'de>>(
+            self,
+            _name: &'static str,
+            fields: &'static [&'static str],
+            visitor: V,
+        ) -> Result<V::Value, Self::Error> {
+            match self {
+                VdfValue::Map(entries) => visitor.visit_map(MapAccess::new(entries, fields)),
+                _ => Err(de::Error::custom("expected map")),
+            }
+        }
+
+        fn deserialize_enum<V: Visitor<'de>>(
+            self,
+            _name: &'static str,
+            _variants: &'static [&'static str],
+            visitor: V,
+        ) -> Result<V::Value, Self::Error> {
+            match self.as_str() {
+                Some(s) => visitor.visit_enum(s.into_deserializer()),
+                None => Err(de::Error::custom("expected string")),
+            }
+        }
+
+        serde::forward_to_deserialize_any! {
+            str string bytes byte_buf unit unit_struct map identifier ignored_any
+        }
+    }
+
+    struct MapAccess<'de, 'a> {
+        entries: std::slice::Iter<'de, (Cow<'a, str>, VdfValue<'a>)>,
+        entry: Option<&'de (Cow<'a, str>, VdfValue<'a>)>,
+        fields: &'static [&'static str],
+    }
+
+    impl<'de, 'a> MapAccess<'de, 'a> {
+        fn new(entries: &'de [(Cow<'a, str>, VdfValue<'a>)], fields: &'static [&'static str]) -> Self {
+            MapAccess {
+                entries: entries.iter(),
+                entry: None,
+                fields,
+            }
+        }
+    }
+
+    impl<'de, 'a> de::MapAccess<'de> for MapAccess<'de, 'a> {
+        type Error = VdfParseError;
+
+        fn next_key_seed<K: de::DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error> {
+            let Some(entry) = self.entries.next() else {
+                return Ok(None);
+            };
+            self.entry = Some(entry);
+            let key: &'de str = &entry.0;
+            let key = self.fields.iter().find(|field| field.eq_ignore_ascii_case(key)).map_or(key, |field| field);
+            seed.deserialize(BorrowedStrDeserializer::new(key)).map(Some)
+        }
+
+        fn next_value_seed<V: de::DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Self::Error> {
+            let (key, value) = self.entry.take().ok_or_else(|| de::Error::custom("value without a key"))?;
+            seed.deserialize(value).map_err(|mut err: VdfParseError| {
+                err.key_path.insert(0, key.to_string());
+                err
+            })
+        }
+    }
+
+    struct SeqAccess<'de, 'a>(std::vec::IntoIter<&'de VdfValue<'a>>);
+
+    impl<'de, 'a> de::SeqAccess<'de> for SeqAccess<'de, 'a> {
+        type Error = VdfParseError;
+
+        fn next_element_seed<T: de::DeserializeSeed<'de>>(&mut self, seed: T) -> Result<Option<T::Value>, Self::Error> {
+            self.0.next().map(|value| seed.deserialize(value)).transpose()
+        }
+    }
+}
+
+/// Serializes `value`, which must be a struct or map, as VDF text.
+///
+/// Numbers and bools become strings since VDF has no other types, `None`
+/// fields are left out and sequences become maps keyed `"0"`, `"1"`, ...
+#[cfg(feature = "serde")]
+pub fn vdf_to_string<T: serde::Serialize + ?Sized>(value: &T) -> Result<String, VdfParseError> {
+    match value.serialize(ser::ValueSerializer)? {
+        value @ VdfValue::Map(_) => Ok(vdf_serialize(&value)),
+        _ => Err(serde::ser::Error::custom("top level value must be a struct or map")),
+    }
+}
+
+// `serde::Serializer` building a `VdfValue` tree for `vdf_to_string`
+#[cfg(feature = "serde")]
+mod ser {
+    use super::VdfParseError;
+    use super::VdfValue;
+    use serde::ser;
+    use serde::Serialize;
+    use std::borrow::Cow;
+
+    type Entries = Vec<(Cow<'static, str>, VdfValue<'static>)>;
+
+    impl ser::Error for VdfParseError {
+        fn custom<T: std::fmt::Display>(msg: T) -> Self {
+            VdfParseError {
+                message: msg.to_string(),
+                key_path: Vec::new(),
+            }
+        }
+    }
+
+    fn string(s: impl ToString) -> VdfValue<'static> {
+        VdfValue::Str(Cow::Owned(s.to_string()))
+    }
+
+    // wraps `value` as `"variant" { ... }` for enum variants with data
+    fn variant(name: Option<&'static str>, value: VdfValue<'static>) -> VdfValue<'static> {
+        match name {
+            Some(name) => VdfValue::Map(vec![(Cow::Borrowed(name), value)]),
+            None => value,
+        }
+    }
+
+    pub(super) struct ValueSerializer;
+
+    impl ser::Serializer for ValueSerializer {
+        type Ok = VdfValue<'static>;
+        type Error = VdfParseError;
+        type SerializeSeq = SeqSerializer;
+        type SerializeTuple = SeqSerializer;
+        type SerializeTupleStruct = SeqSerializer;
+        type SerializeTupleVariant = SeqSerializer;
+        type SerializeMap = MapSerializer;
+        type SerializeStruct = MapSerializer;
+        type SerializeStructVariant = MapSerializer;
+
+        fn serialize_bool(self, v: bool) -> Result<Self::Ok, Self::Error> {
+            Ok(string(if v { "1" } else { "0" }))
+        }
+
+        fn serialize_i8(self, v: i8) -> Result<Self::Ok, Self::Error> {
+            Ok(string(v))
+        }
+
+        fn serialize_i16(self, v: i16) -> Result<Self::Ok, Self::Error> {
+            Ok(string(v))
+        }
+
+        fn serialize_i32(self, v: i32) -> Result<Self::Ok, Self::Error> {
+            Ok(string(v))
+        }
+
+        fn serialize_i64(self, v: i64) -> Result<Self::Ok, Self::Error> {
+            Ok(string(v))
+        }
+
+        fn serialize_u8(self, v: u8) -> Result<Self::Ok, Self::Error> {
+            Ok(string(v))
+        }
+
+        fn serialize_u16(self, v: u16) -> Result<Self::Ok, Self::Error> {
+            Ok(string(v))
+        }
+
+        fn serialize_u32(self, v: u32) -> Result<Self::Ok, Self::Error> {
+            Ok(string(v))
+        }
+
+        fn serialize_u64(self, v: u64) -> Result<Self::Ok, Self::Error> {
+            Ok(string(v))
+        }
+
+        fn serialize_f32(self, v: f32) -> Result<Self::Ok, Self::Error> {
+            Ok(string(v))
+        }
+
+        fn serialize_f64(self, v: f64) -> Result<Self::Ok, Self::Error> {
+            Ok(string(v))
+        }
+
+        fn serialize_char(self, v: char) -> Result<Self::Ok, Self::Error> {
+            Ok(string(v))
+        }
+
+        fn serialize_str(self, v: &str) -> Result<Self::Ok, Self::Error> {
+            Ok(string(v))
+        }
+
+        fn serialize_bytes(self, _v: &[u8]) -> Result<Self::Ok, Self::Error> {
+            Err(ser::Error::custom("bytes are not supported"))
+        }
+
+        fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
+            Ok(VdfValue::Null)
+        }
+
+        fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<Self::Ok, Self::Error> {
+            value.serialize(self)
+        }
+
+        fn serialize_unit(self) -> Result<Self::Ok, Self::Error> {
+            Ok(VdfValue::Null)
+        }
+
+        fn serialize_unit_struct(self, _name: &'static str) -> Result<Self::Ok, Self::Error> {
+            Ok(VdfValue::Null)
+        }
+
+        fn serialize_unit_variant(
+            self,
+            _name: &'static str,
+            _variant_index: u32,
+            variant: &'static str,
+        ) -> Result<Self::Ok, Self::Error> {
+            Ok(string(variant))
+        }
+
+        fn serialize_newtype_struct<T: Serialize + ?Sized>(
+            self,
+            _name: &'static str,
+            value: &T,
+        ) -> Result<Self::Ok, Self::Error> {
+            value.serialize(self)
+        }
+
+        fn serialize_newtype_variant<T: Serialize + ?Sized>(
+            self,
+            _name: &'static str,
+            _variant_index: u32,
+            name: &'static str,
+            value: &T,
+        ) -> Result<Self::Ok, Self::Error> {
+            Ok(variant(Some(name), value.serialize(self)?))
+        }
+
+        fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
+            Ok(SeqSerializer {
+                entries: Vec::with_capacity(len.unwrap_or(0)),
+                variant: None,
+            })
+        }
+
+        fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, Self::Error> {
+            self.serialize_seq(Some(len))
+        }
+
+        fn serialize_tuple_struct(
+            self,
+            _name: &'static str,
+            len: usize,
+        ) -> Result<Self::SerializeTupleStruct, Self::Error> {
+            self.serialize_seq(Some(len))
+        }
+
+        fn serialize_tuple_variant(
+            self,
+            _name: &'static str,
+            _variant_index: u32,
+            name: &'static str,
+            len: usize,
+        ) -> Result<Self::SerializeTupleVariant, Self::Error> {
+            Ok(SeqSerializer {
+                entries: Vec::with_capacity(len),
+                variant: Some(name),
+            })
+        }
+
+        fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
+            Ok(MapSerializer {
+                entries: Vec::with_capacity(len.unwrap_or(0)),
+                key: None,
+                variant: None,
+            })
+        }
+
+        fn serialize_struct(self, _name: &'static str, len: usize) -> Result<Self::SerializeStruct, Self::Error> {
+            self.serialize_map(Some(len))
+        }
+
+        fn serialize_struct_variant(
+            self,
+            _name: &'static str,
+            _variant_index: u32,
+            name: &'static str,
+            len: usize,
+        ) -> Result<Self::SerializeStructVariant, Self::Error> {
+            Ok(MapSerializer {
+                entries: Vec::with_capacity(len),
+                key: None,
+                variant: Some(name),
+            })
+        }
+    }
+
+    pub(super) struct SeqSerializer {
+        entries: Entries,
+        variant: Option<&'static str>,
+    }
+
+    impl SeqSerializer {
+        fn push<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), VdfParseError> {
+            let index = self.entries.len().to_string();
+            self.entries.push((Cow::Owned(index), value.serialize(ValueSerializer)?));
+            Ok(())
+        }
+
+        fn finish(self) -> VdfValue<'static> {
+            variant(self.variant, VdfValue::Map(self.entries))
+        }
+    }
+
+    impl ser::SerializeSeq for SeqSerializer {
+        type Ok = VdfValue<'static>;
+        type Error = VdfParseError;
+
+        fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Self::Error> {
+            self.push(value)
+        }
+
+        fn end(self) -> Result<Self::Ok, Self::Error> {
+            Ok(self.finish())
+        }
+    }
+
+    impl ser::SerializeTuple for SeqSerializer {
+        type Ok = VdfValue<'static>;
+        type Error = VdfParseError;
+
+        fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Self::Error> {
+            self.push(value)
+        }
+
+        fn end(self) -> Result<Self::Ok, Self::Error> {
+            Ok(self.finish())
+        }
+    }
+
+    impl ser::SerializeTupleStruct for SeqSerializer {
+        type Ok = VdfValue<'static>;
+        type Error = VdfParseError;
+
+        fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Self::Error> {
+            self.push(value)
+        }
+
+        fn end(self) -> Result<Self::Ok, Self::Error> {
+            Ok(self.finish())
+        }
+    }
+
+    impl ser::SerializeTupleVariant for SeqSerializer {
+        type Ok = VdfValue<'static>;
+        type Error = VdfParseError;
+
+        fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Self::Error> {
+            self.push(value)
+        }
+
+        fn end(self) -> Result<Self::Ok, Self::Error> {
+            Ok(self.finish())
+        }
+    }
+
+    pub(super) struct MapSerializer {
+        entries: Entries,
+        key: Option<Cow<'static, str>>,
+        variant: Option<&'static str>,
+    }
+
+    impl MapSerializer {
+        fn push<T: Serialize + ?Sized>(&mut self, key: Cow<'static, str>, value: &T) -> Result<(), VdfParseError> {
+            let value = value.serialize(ValueSerializer).map_err(|mut err| {
+                err.key_path.insert(0, key.to_string());
+                err
+            })?;
+            self.entries.push((key, value));
+            Ok(())
+        }
+
+        fn finish(self) -> VdfValue<'static> {
+            variant(self.variant, VdfValue::Map(self.entries))
+        }
+    }
+
+    impl ser::SerializeMap for MapSerializer {
+        type Ok = VdfValue<'static>;
+        type Error = VdfParseError;
+
+        fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), Self::Error> {
+            match key.serialize(ValueSerializer)? {
+                VdfValue::Str(key) => {
+                    self.key = Some(key);
+                    Ok(())
+                }
+                _ => Err(ser::Error::custom("map keys must be strings or numbers")),
+            }
+        }
+
+        fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Self::Error> {
+            let key = self.key.take().ok_or_else(|| ser::Error::custom("value without a key"))?;
+            self.push(key, value)
+        }
+
+        fn end(self) -> Result<Self::Ok, Self::Error> {
+            Ok(self.finish())
+        }
+    }
+
+    impl ser::SerializeStruct for MapSerializer {
+        type Ok = VdfValue<'static>;
+        type Error = VdfParseError;
+
+        fn serialize_field<T: Serialize + ?Sized>(&mut self, key: &'static str, value: &T) -> Result<(), Self::Error> {
+            self.push(Cow::Borrowed(key), value)
+        }
+
+        fn end(self) -> Result<Self::Ok, Self::Error> {
+            Ok(self.finish())
+        }
+    }
+
+    impl ser::SerializeStructVariant for MapSerializer {
+        type Ok = VdfValue<'static>;
+        type Error = VdfParseError;
+
+        fn serialize_field<T: Serialize + ?Sized>(&mut self, key: &'static str, value: &T) -> Result<(), Self::Error> {
+            self.push(Cow::Borrowed(key), value)
+        }
+
+        fn end(self) -> Result<Self::Ok, Self::Error> {
+            Ok(self.finish())
+        }
+    }
+}
+
+/// Renders `value` as VDF text that [`vdf_parse`] reads back.
+pub fn vdf_serialize(value: &VdfValue) -> String {
+    value.to_string()
+}
+
+/// Error from parsing VDF text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VdfParseError {
+    pub message: String,
+    /// Keys of the maps enclosing the error, outermost first.
+    pub key_path: Vec<String>,
+}
+
+impl std::fmt::Display for VdfParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)?;
+        if !self.key_path.is_empty() {
+            write!(f, " in \"{}\"", self.key_path.join("/"))?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for VdfParseError {}
+
+impl From<VdfParseError> for io::Error {
+    fn from(err: VdfParseError) -> Self {
+        io::Error::new(io::ErrorKind::InvalidData, err)
+    }
+}
+
+/// Parses text VDF such as `config.vdf`, `localconfig.vdf` or an `.acf`
+/// manifest.
+///
+/// Flat files of top-level key-value pairs with no enclosing map parse the
+/// same way, into a map of those pairs. `key=value` files such as
+/// `steam.cfg` are not VDF.
+///
+/// The result borrows from the input, so keep the buffer alive or call
+/// [`VdfValue::into_owned`].
+pub fn vdf_parse(input: &str) -> Result<VdfValue<'_>, VdfParseError> {
+    VdfParserOptions::new().parse(input)
+}
+
+/// How [`vdf_parse_bytes`] treats input that isn't valid UTF-8.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Utf8Mode {
+    /// Fail on the first invalid sequence.
+    Strict,
+    /// Replace invalid sequences with `U+FFFD`.
+    LossyReplace,
+}
+
+/// Like [`vdf_parse`] for text VDF that may not be valid UTF-8.
+pub fn vdf_parse_bytes(input: &[u8], mode: Utf8Mode) -> Result<VdfValue<'_>, VdfParseError> {
+    let text = match mode {
+        Utf8Mode::Strict => Cow::Borrowed(std::str::from_utf8(input).map_err(|err| VdfParseError {
+            message: err.to_string(),
+            key_path: Vec::new(),
+        })?),
+        Utf8Mode::LossyReplace => String::from_utf8_lossy(input),
+    };
+    match text {
+        Cow::Borrowed(text) => vdf_parse(text),
+        Cow::Owned(text) => vdf_parse(&text).map(VdfValue::into_owned),
+    }
+}
+
+/// Parses `input` with escaped strings copied into `arena` instead of separate
+/// heap allocations.
+#[cfg(feature = "typed-arena")]
+pub fn vdf_parse_in_arena<'a>(
+    input: &'a str,
+    arena: &'a typed_arena::Arena<u8>,
+) -> Result<VdfValue<'a>, VdfParseError> {
+    VdfParserOptions::new().parse_with(input, &|s| Cow::Borrowed(&*arena.alloc_str(s)))
+}
+
+/// Parses binary VDF as used by `shortcuts.vdf` and some `localconfig.vdf`
+/// files.
+///
+/// Numbers are stored as strings so they read back through
+/// [`VdfValue::as_int`].
+pub fn vdf_parse_binary(mut bytes: &[u8]) -> Result<VdfValue<'static>, VdfParseError> {
+    fn cstr(bytes: &mut &[u8]) -> Option<String> {
+        let end = bytes.iter().position(|&b| b == 0)?;
+        let s = String::from_utf8_lossy(&bytes[..end]).into_owned();
+        *bytes = &bytes[end + 1..];
+        Some(s)
+    }
+
+    fn take<const N: usize>(bytes: &mut &[u8]) -> Option<[u8; N]> {
+        let value = bytes.get(..N)?.try_into().ok()?;
+        *bytes = &bytes[N..];
+        Some(value)
+    }
+
+    fn map(bytes: &mut &[u8], stack: &mut Vec<String>) -> Result<VdfValue<'static>, &'static str> {
+        let mut entries = Vec::new();
+        loop {
+            let Some((&kind, rest)) = bytes.split_first() else {
+                // tolerate a missing end byte for the root map
+                if stack.is_empty() {
+                    return Ok(VdfValue::Map(entries));
+                }
+                return Err("unexpected end of input");
+            };
+            *bytes = rest;
+            if kind == 0x08 {
+                return Ok(VdfValue::Map(entries));
+            }
+            let key = cstr(bytes).ok_or("unterminated key")?;
+            let value = match kind {
+                0x00 => {
+                    stack.push(key.clone());
+                    let value = map(bytes, stack)?;
+                    stack.pop();
+                    value
+                }
+                0x01 => VdfValue::Str(Cow::Owned(cstr(bytes).ok_or("unterminated string")?)),
+                0x02 => {
+                    let n = i32::from_le_bytes(take(bytes).ok_or("truncated int")?);
+                    VdfValue::Str(Cow::Owned(n.to_string()))
+                }
+                0x03 => {
+                    let n = f32::from_le_bytes(take(bytes).ok_or("truncated float")?);
+                    VdfValue::Str(Cow::Owned(n.to_string()))
+                }
+                0x07 => {
+                    let n = u64::from_le_bytes(take(bytes).ok_or("truncated int")?);
+                    VdfValue::Str(Cow::Owned(n.to_string()))
+                }
+                _ => return Err("unknown value type"),
+            };
+            entries.push((Cow::Owned(key), value));
+        }
+    }
+
+    let mut stack = Vec::new();
+    map(&mut bytes, &mut stack).map_err(|msg| VdfParseError {
+        message: msg.to_string(),
+        key_path: stack,
+    })
+}
+
+// returned by `parse_str` when the input ends before the closing quote
+const UNEXPECTED_END: &str = "unexpected end of input";
+
+// reads up to the closing quote, borrowing from `chars` unless there are
+// escapes to replace
+fn parse_str<'a>(
+    chars: &mut Chars<'a>,
+    scratch: &mut String,
+    alloc: &dyn Fn(&str) -> Cow<'a, str>,
+) -> Result<Cow<'a, str>, &'static str> {
+    let buf = chars.as_str();
+    // most strings have no escapes, so look for the end with a byte scan
+    // before falling back to decoding one char at a time
+    let mut len = buf.bytes().position(|b| matches!(b, b'"' | b'\\' | 0)).unwrap_or(buf.len());
+    if buf.as_bytes().get(len) == Some(&b'"') {
+        *chars = buf[len + 1..].chars();
+        return Ok(Cow::Borrowed(&buf[..len]));
+    }
+    *chars = buf[len..].chars();
+    let mut owned = false;
+    let mut is_escaped = false;
+    let mut closed = false;
+    while let Some(next) = chars.next() {
+        if is_escaped {
+            is_escaped = false;
+            if !owned {
+                owned = true;
+                scratch.clear();
+                scratch.push_str(&buf[..len]);
+            }
+            match next {
+                '"' => scratch.push('"'),
+                'r' => scratch.push('\r'),
+                'n' => scratch.push('\n'),
+                't' => scratch.push('\t'),
+                'v' => scratch.push('\u{b}'),
+                'b' => scratch.push('\u{8}'),
+                'f' => scratch.push('\u{c}'),
+                'a' => scratch.push('\u{7}'),
+                '\\' | '?' | '\'' => scratch.push(next),
+                'x' => scratch.push(parse_hex_escape(chars)?),
+                // not an escape KeyValues knows, keep it as written
+                _ => {
+                    scratch.push('\\');
+                    scratch.push(next);
+                }
+            }
+        } else {
+            match next {
+                '"' => {
+                    closed = true;
+                    break;
+                }
+                '\\' => is_escaped = true,
+                '\0' => return Err("null byte in string"),
+                _ => {
+                    if owned {
+                        scratch.push(next);
+                    } else {
+                        len += next.len_utf8();
+                    }
+                }
+            }
+        }
+    }
+    if !closed {
+        return Err(UNEXPECTED_END);
+    }
+    Ok(if owned {
+        alloc(scratch)
+    } else {
+        Cow::Borrowed(&buf[..len])
+    })
+}
+
+// `\xHH` or `\x{H..}` with up to 6 hex digits
+fn parse_hex_escape(chars: &mut Chars) -> Result<char, &'static str> {
+    let digits = if chars.as_str().starts_with('{') {
+        chars.next();
+        let rest = chars.as_str();
+        let end = rest.find('}').ok_or("unterminated hex escape")?;
+        chars.nth(end);
+        &rest[..end]
+    } else {
+        let rest = chars.as_str();
+        let end = rest.char_indices().nth(2).map_or(rest.len(), |(i, _)| i);
+        chars.nth(1);
+        &rest[..end]
+    };
+    if digits.is_empty() || digits.len() > 6 || !digits.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return Err("invalid hex escape");
+    }
+    match u32::from_str_radix(digits, 16).ok().and_then(char::from_u32) {
+        Some('\0') => Err("null byte in string"),
+        Some(c) => Ok(c),
+        None => Err("invalid hex escape"),
+    }
+}
+
+/// An item from [`stream_vdf_events`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VdfEvent {
+    StartMap(String),
+    EndMap,
+    KeyValue(String, String),
+}
+
+/// Reads VDF from `input` one event at a time instead of building a tree.
+///
+/// The iterator stops after the first error.
+pub fn stream_vdf_events(input: impl io::Read) -> impl Iterator<Item = io::Result<VdfEvent>> {
+    VdfParserOptions::new().stream(input)
+}
+
+struct VdfEvents<R> {
+    tokens: VdfTokens<'static, ReadSource<R>>,
+    done: bool,
+}
+
+impl<R: io::Read> Iterator for VdfEvents<R> {
+    type Item = io::Result<VdfEvent>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let event = match self.tokens.next_token() {
+            Ok(Some(Token::StartMap(key))) => VdfEvent::StartMap(key.into_owned()),
+            Ok(Some(Token::EndMap)) => VdfEvent::EndMap,
+            Ok(Some(Token::KeyValue(key, value))) => VdfEvent::KeyValue(key.into_owned(), value.into_owned()),
+            Ok(None) => {
+                self.done = true;
+                return None;
+            }
+            Err(err) => {
+                self.done = true;
+                // a failed read shows up as a parse error, report the cause
+                return Some(Err(self.tokens.source.error.take().unwrap_or_else(|| err.into())));
+            }
+        };
+        Some(Ok(event))
+    }
+}
+
+/// Settings for parsing text VDF, e.g. to accept malformed files from third
+/// party tools that [`vdf_parse`] rejects.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct VdfParserOptions {
+    lenient: bool,
+}
+
+impl VdfParserOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// On an unexpected token skip to the next line instead of failing, and
+    /// close any maps left open at the end of the input.
+    pub fn lenient(mut self, lenient: bool) -> Self {
+        self.lenient = lenient;
+        self
+    }
+
+    /// Parses `input` like [`vdf_parse`] with these settings.
+    pub fn parse<'a>(&self, input: &'a str) -> Result<VdfValue<'a>, VdfParseError> {
+        self.parse_with(input, &|s| Cow::Owned(s.to_string()))
+    }
+
+    /// Reads `input` like [`stream_vdf_events`] with these settings.
+    pub fn stream<R: io::Read>(&self, input: R) -> impl Iterator<Item = io::Result<VdfEvent>> {
+        VdfEvents {
+            tokens: VdfTokens::new(ReadSource::new(input), self.lenient),
+            done: false,
+        }
+    }
+
+    // `alloc` stores strings that couldn't be borrowed from the input
+    fn parse_with<'a>(
+        &self,
+        input: &'a str,
+        alloc: &dyn Fn(&str) -> Cow<'a, str>,
+    ) -> Result<VdfValue<'a>, VdfParseError> {
+        type Entries<'a> = Vec<(Cow<'a, str>, VdfValue<'a>)>;
+
+        let mut tokens = VdfTokens::new(StrSource::new(input, alloc), self.lenient);
+        let mut stack: Vec<(Entries<'a>, Cow<'a, str>)> = Vec::with_capacity(16);
+        let mut map: Entries<'a> = Vec::new();
+        while let Some(token) = tokens.next_token()? {
+            match token {
+                Token::StartMap(key) => {
+                    map.sort_unstable_by(|a, b| a.0.cmp(&b.0));
+                    stack.push((std::mem::take(&mut map), key));
+                }
+                Token::EndMap => {
+                    if let Some((mut parent, key)) = stack.pop() {
+                        parent.push((key, VdfValue::Map(map)));
+                        map = parent;
+                    }
+                }
+                Token::KeyValue(key, value) => map.push((key, VdfValue::Str(value))),
+            }
+        }
+        map.sort_unstable_by(|a, b| a.0.cmp(&b.0));
+        Ok(VdfValue::Map(map))
+    }
+}
+
+// some editors save VDF files with a UTF-8 byte order mark
+const BOM: char = '\u{feff}';
+
+// where `VdfTokens` reads its input from
+trait VdfSource<'a> {
+    // position of the next char, only turned into a line and column when an
+    // error needs one
+    type Mark: Copy;
+
+    fn mark(&self) -> Self::Mark;
+    fn locate(&self, mark: Self::Mark) -> (usize, usize);
+    fn next_char(&mut self) -> Result<Option<char>, &'static str>;
+    fn peek_char(&mut self) -> Result<Option<char>, &'static str>;
+    // reads up to and including the closing quote, see `parse_str`
+    fn read_str(&mut self, scratch: &mut String) -> Result<Cow<'a, str>, &'static str>;
+}
+
+// text already in memory, borrowed from where possible
+struct StrSource<'a, 'f> {
+    input: &'a str,
+    chars: Chars<'a>,
+    alloc: &'f dyn Fn(&str) -> Cow<'a, str>,
+}
+
+impl<'a, 'f> StrSource<'a, 'f> {
+    fn new(input: &'a str, alloc: &'f dyn Fn(&str) -> Cow<'a, str>) -> Self {
+        // the BOM must not count towards error columns
+        let input = input.strip_prefix(BOM).unwrap_or(input);
+        StrSource {
+            input,
+            chars: input.chars(),
+            alloc,
+        }
+    }
+}
+
+impl<'a> VdfSource<'a> for StrSource<'a, '_> {
+    // bytes left in the input, so the parser doesn't have to count lines as
+    // it goes
+    type Mark = usize;
+
+    fn mark(&self) -> usize {
+        self.chars.as_str().len()
+    }
+
+    fn locate(&self, rest: usize) -> (usize, usize) {
+        let before = &self.input[..self.input.len() - rest];
+        let line = before.matches('\n').count() + 1;
+        let column = before.rsplit('\n').next().unwrap_or("").chars().count() + 1;
+        (line, column)
+    }
+
+    fn next_char(&mut self) -> Result<Option<char>, &'static str> {
+        Ok(self.chars.next())
+    }
+
+    fn peek_char(&mut self) -> Result<Option<char>, &'static str> {
+        Ok(self.chars.clone().next())
+    }
+
+    fn read_str(&mut self, scratch: &mut String) -> Result<Cow<'a, str>, &'static str> {
+        parse_str(&mut self.chars, scratch, self.alloc)
+    }
+}
+
+// text decoded from a reader as it is needed
+struct ReadSource<R> {
+    bytes: io::Bytes<io::BufReader<R>>,
+    started: bool,
+    peeked: Option<char>,
+    line: usize,
+    column: usize,
+    raw: String,
+    // the read error behind a "failed to read input"
+    error: Option<io::Error>,
+}
+
+impl<R: io::Read> ReadSource<R> {
+    fn new(input: R) -> Self {
+        ReadSource {
+            bytes: io::Read::bytes(io::BufReader::new(input)),
+            started: false,
+            peeked: None,
+            line: 1,
+            column: 1,
+            raw: String::new(),
+            error: None,
+        }
+    }
+
+    fn read_byte(&mut self) -> Result<Option<u8>, &'static str> {
+        match self.bytes.next().transpose() {
+            Ok(byte) => Ok(byte),
+            Err(err) => {
+                self.error = Some(err);
+                Err("failed to read input")
+            }
+        }
+    }
+
+    fn decode(&mut self) -> Result<Option<char>, &'static str> {
+        let Some(first) = self.read_byte()? else {
+            return Ok(None);
+        };
+        let len = match first {
+            0x00..=0x7f => return Ok(Some(first as char)),
+            0xc0..=0xdf => 2,
+            0xe0..=0xef => 3,
+            0xf0..=0xf7 => 4,
+            _ => return Err("invalid UTF-8"),
+        };
+        let mut buf = [first, 0, 0, 0];
+        for byte in &mut buf[1..len] {
+            *byte = self.read_byte()?.ok_or("invalid UTF-8")?;
+        }
+        let s = std::str::from_utf8(&buf[..len]).map_err(|_| "invalid UTF-8")?;
+        Ok(s.chars().next())
+    }
+}
+
+impl<R: io::Read> VdfSource<'static> for ReadSource<R> {
+    type Mark = (usize, usize);
+
+    fn mark(&self) -> (usize, usize) {
+        (self.line, self.column)
+    }
+
+    fn locate(&self, mark: (usize, usize)) -> (usize, usize) {
+        mark
+    }
+
+    fn next_char(&mut self) -> Result<Option<char>, &'static str> {
+        let next = self.peek_char()?;
+        self.peeked = None;
+        match next {
+            Some('\n') => {
+                self.line += 1;
+                self.column = 1;
+            }
+            Some(_) => self.column += 1,
+            None => {}
+        }
+        Ok(next)
+    }
+
+    fn peek_char(&mut self) -> Result<Option<char>, &'static str> {
+        if self.peeked.is_none() {
+            let mut next = self.decode()?;
+            if !self.started {
+                self.started = true;
+                if next == Some(BOM) {
+                    next = self.decode()?;
+                }
+            }
+            self.peeked = next;
+        }
+        Ok(self.peeked)
+    }
+
+    fn read_str(&mut self, scratch: &mut String) -> Result<Cow<'static, str>, &'static str> {
+        let mut raw = std::mem::take(&mut self.raw);
+        raw.clear();
+        let mut is_escaped = false;
+        loop {
+            let next = self.next_char()?.ok_or(UNEXPECTED_END)?;
+            raw.push(next);
+            if next == '"' && !is_escaped {
+                break;
+            }
+            is_escaped = next == '\\' && !is_escaped;
+        }
+        let s = parse_str(&mut raw.chars(), scratch, &|s| Cow::Owned(s.to_string())).map(|s| s.into_owned());
+        self.raw = raw;
+        Ok(Cow::Owned(s?))
+    }
+}
+
+// what the token loop found next
+enum Token<'a> {
+    StartMap(Cow<'a, str>),
+    EndMap,
+    KeyValue(Cow<'a, str>, Cow<'a, str>),
+}
+
+// the token loop shared by `VdfParserOptions::parse` and `stream_vdf_events`
+struct VdfTokens<'a, S> {
+    source: S,
+    lenient: bool,
+    key: Option<Cow<'a, str>>,
+    // keys of the open maps, for `VdfParseError::key_path`
+    stack: Vec<Cow<'a, str>>,
+    // the input ended inside a string
+    cut_off: bool,
+    scratch: String,
+}
+
+impl<'a, S: VdfSource<'a>> VdfTokens<'a, S> {
+    fn new(source: S, lenient: bool) -> Self {
+        VdfTokens {
+            source,
+            lenient,
+            key: None,
+            stack: Vec::with_capacity(16),
+            cut_off: false,
+            scratch: String::new(),
+        }
+    }
+
+    fn error(&self, message: &str, mark: S::Mark) -> VdfParseError {
+        let (line, column) = self.source.locate(mark);
+        VdfParseError {
+            message: format!("{message} at line {line} column {column}"),
+            key_path: self.stack.iter().map(|key| key.to_string()).collect(),
+        }
+    }
+
+    fn skip_line(&mut self) -> Result<(), &'static str> {
+        while let Some(next) = self.source.next_char()? {
+            if next == '\n' {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    // `None` if the input ends before the closing quote
+    fn read_str(&mut self, at: S::Mark) -> Result<Option<Cow<'a, str>>, VdfParseError> {
+        match self.source.read_str(&mut self.scratch) {
+            Ok(s) => Ok(Some(s)),
+            Err(UNEXPECTED_END) => {
+                self.cut_off = true;
+                Ok(None)
+            }
+            Err(e) => Err(self.error(e, at)),
+        }
+    }
+
+    fn next_token(&mut self) -> Result<Option<Token<'a>>, VdfParseError> {
+        loop {
+            let at = self.source.mark();
+            let Some(start) = self.source.next_char().map_err(|e| self.error(e, at))? else {
+                return self.finish();
+            };
+            if start.is_ascii_whitespace() {
+                continue;
+            }
+
+            // `//` comments run to the end of the line, a lone `/` is still
+            // an unexpected token
+            if start == '/' && self.source.peek_char().map_err(|e| self.error(e, at))? == Some('/') {
+                self.skip_line().map_err(|e| self.error(e, at))?;
+                continue;
+            }
+
+            match (self.key.take(), start) {
+                (None, '"') => match self.read_str(at)? {
+                    Some(key) => self.key = Some(key),
+                    None => return self.finish(),
+                },
+                (None, '}') => {
+                    // the root map has no braces so there is nothing to close
+                    if self.stack.pop().is_some() {
+                        return Ok(Some(Token::EndMap));
+                    }
+                    if !self.lenient {
+                        return Err(self.error("unmatched '}'", at));
+                    }
+                }
+                (Some(key), '"') => match self.read_str(at)? {
+                    Some(value) => return Ok(Some(Token::KeyValue(key, value))),
+                    None => return self.finish(),
+                },
+                (Some(key), '{') => {
+                    self.stack.push(key.clone());
+                    return Ok(Some(Token::StartMap(key)));
+                }
+                _ => {
+                    if !self.lenient {
+                        return Err(self.error(&format!("unexpected token '{start}'"), at));
+                    }
+                    self.skip_line().map_err(|e| self.error(e, at))?;
+                }
+            }
+        }
+    }
+
+    // a key without a value, a cut off string or an unclosed map means the
+    // input was cut off, lenient mode closes the maps one at a time
+    fn finish(&mut self) -> Result<Option<Token<'a>>, VdfParseError> {
+        if self.key.is_some() || self.cut_off || !self.stack.is_empty() {
+            if !self.lenient {
+                return Err(self.error(UNEXPECTED_END, self.source.mark()));
+            }
+            self.key = None;
+            self.cut_off = false;
+            if self.stack.pop().is_some() {
+                return Ok(Some(Token::EndMap));
+            }
+        }
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    // a fresh directory under the system temp dir, removed again on drop
+    // even when an assertion fails first
+    struct TempDir(std::path::PathBuf);
+
+    impl TempDir {
+        fn new(name: &str) -> TempDir {
+            let path = std::env::temp_dir().join(format!("steam_find_{name}_{}", std::process::id()));
+            let _ = std::fs::remove_dir_all(&path);
+            std::fs::create_dir_all(&path).unwrap();
+            TempDir(path)
+        }
+    }
+
+    impl std::ops::Deref for TempDir {
+        type Target = std::path::Path;
+
+        fn deref(&self) -> &std::path::Path {
+            &self.0
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn app(name: &str) -> crate::App {
+        crate::App {
+            app_id: crate::AppId(0),
+            name: name.to_string(),
+            size_on_disk: 0,
+            path: Default::default(),
+            library_path: Default::default(),
+            installdir: String::new(),
+            last_updated: None,
+            auto_update_paused: false,
+            scheduled_auto_update_time: None,
+            installed_depots: Vec::new(),
+            state_flags: 0,
+            build_id: 0,
+            acf_path: Default::default(),
+            last_owner: None,
+            app_type: crate::AppType::Other(String::new()),
+        }
+    }
+
+    #[test]
+    fn dedup_by_name() {
+        let apps = [(1, "Game", 5), (2, "Game", 7), (3, "", 0), (4, "", 0)].map(|(app_id, name, build_id)| {
+            crate::App {
+                app_id: crate::AppId(app_id),
+                build_id,
+                ..app(name)
+            }
+        });
+        let ids = crate::dedup_by_name(apps.into()).iter().map(|app| app.app_id.0).collect::<Vec<_>>();
+        assert_eq!(ids, [2, 3, 4]);
+    }
+
+    #[test]
+    fn parse() {
+        let ast = crate::vdf_parse(r#"
+            "AppState"
+            {
+                "appid"     "55500"
+                "name"      "Test Game"
+                "UserConfig"
+                {
+                    "language"      "english"
+                }
+            }
+        "#).unwrap();
+
+        assert_eq!(ast["AppState"]["appid"].as_int(), Some(55500));
+        assert_eq!(ast["AppState"]["name"].as_str(), Some("Test Game"));
+        assert_eq!(ast["AppState"]["UserConfig"]["language"].as_str(), Some("english"));
+    }
+
+    #[test]
+    fn utf8() {
+        crate::vdf_parse(r#"
+            "ᚠ" {}
+        "#).unwrap();
+    }
+
+    #[test]
+    fn empty_map() {
+        let ast = crate::vdf_parse(r#""AppState" { "UserConfig" {} "MountedConfig" { } "name" "Game" }"#).unwrap();
+        assert_eq!(ast["AppState"]["UserConfig"], crate::VdfValue::Map(vec![]));
+        assert_eq!(ast["AppState"]["MountedConfig"], crate::VdfValue::Map(vec![]));
+        assert_eq!(ast["AppState"]["name"].as_str(), Some("Game"));
+        assert_eq!(crate::vdf_parse(r#""AppState" {}"#).unwrap()["AppState"], crate::VdfValue::Map(vec![]));
+    }
+
+    #[test]
+    fn escaped_characters() {
+        let ast = crate::vdf_parse(r#"
+            "AppState" { "name" "\\" }
+        "#).unwrap();
+        assert_eq!(Some(r"\"), ast["appstate"]["name"].as_str(), "{ast:?}");
+    }
+
+    #[test]
+    fn hex_escapes() {
+        let ast = crate::vdf_parse(r#""name" "\x41\x{e9}\x{1F600}""#).unwrap();
+        assert_eq!(Some("A\u{e9}\u{1F600}"), ast["name"].as_str());
+        assert!(crate::vdf_parse(r#""name" "\x4""#).is_err());
+        assert!(crate::vdf_parse(r#""name" "\x{110000}""#).is_err());
+        assert!(crate::vdf_parse(r#""name" "a\x00b""#).unwrap_err().message.starts_with("null byte in string"));
+        assert!(crate::vdf_parse(r#""name" "a\x{0}b""#).is_err());
+    }
+
+    #[test]
+    fn keyvalues_escapes() {
+        let ast = crate::vdf_parse(r#""LaunchOptions" "a\tb\v\b\f\a\?\'\q""#).unwrap();
+        assert_eq!(Some("a\tb\u{b}\u{8}\u{c}\u{7}?'\\q"), ast["LaunchOptions"].as_str());
+        let events = crate::stream_vdf_events(&br#""LaunchOptions" "a\tb""#[..]).collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(events, [crate::VdfEvent::KeyValue("LaunchOptions".into(), "a\tb".into())]);
+    }
+
+    #[test]
+    fn installdir_names() {
+        assert!(crate::is_plain_dir_name(Some("Team Fortress 2")));
+        assert!(!crate::is_plain_dir_name(Some("../../etc")));
+        assert!(!crate::is_plain_dir_name(Some("/opt/game")));
+        assert!(!crate::is_plain_dir_name(Some("")));
+        assert!(!crate::is_plain_dir_name(None));
+    }
+
+    #[test]
+    fn parse_bytes() {
+        use crate::Utf8Mode;
+        let input = b"\"AppState\" { \"name\" \"Caf\xe9\" \"appid\" \"440\" }";
+        assert!(crate::vdf_parse_bytes(input, Utf8Mode::Strict).is_err());
+        let ast = crate::vdf_parse_bytes(input, Utf8Mode::LossyReplace).unwrap();
+        assert_eq!(ast["AppState"]["name"].as_str(), Some("Caf\u{fffd}"));
+        assert_eq!(ast["AppState"]["appid"].as_int(), Some(440));
+        let ast = crate::vdf_parse_bytes("\"name\" \"Café\"".as_bytes(), Utf8Mode::Strict).unwrap();
+        assert_eq!(ast["name"].as_str(), Some("Café"));
+    }
+
+    #[test]
+    fn comments() {
+        use crate::VdfEvent;
+        let input = r#"
+            "AppState"
+            {
+                "appid"     "55500" // trailing
+                // "name"   "Commented Out"
+                "name"      "Test Game"
+            }
+        "#;
+        let ast = crate::vdf_parse(input).unwrap();
+        assert_eq!(ast["AppState"]["appid"].as_int(), Some(55500));
+        assert_eq!(ast["AppState"]["name"].as_str(), Some("Test Game"));
+        assert_eq!(ast["AppState"].len(), 2);
+        assert!(crate::vdf_parse(r#""appid" / "55500""#).is_err());
+
+        let events = crate::stream_vdf_events(input.as_bytes()).collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(events, [
+            VdfEvent::StartMap("AppState".into()),
+            VdfEvent::KeyValue("appid".into(), "55500".into()),
+            VdfEvent::KeyValue("name".into(), "Test Game".into()),
+            VdfEvent::EndMap,
+        ]);
+        assert!(crate::stream_vdf_events(&br#""appid" / "55500""#[..]).any(|event| event.is_err()));
+    }
+
+    #[test]
+    fn lenient() {
+        let input = r#"
+            "AppState"
+            {
+                "appid"     "55500"
+                garbage
+                "name"      "Test Game"
+            }
+        "#;
+        assert!(crate::vdf_parse(input).is_err());
+
+        let ast = crate::VdfParserOptions::new().lenient(true).parse(input).unwrap();
+        assert_eq!(ast["AppState"]["appid"].as_int(), Some(55500));
+        assert_eq!(ast["AppState"]["name"].as_str(), Some("Test Game"));
+    }
+
+    #[test]
+    fn flat() {
+        let ast = crate::vdf_parse(r#"
+            "BootStrapperInhibitAll"    "enable"
+            "BootStrapperForceSelfUpdate"   "disable"
+        "#).unwrap();
+        assert_eq!(ast["BootStrapperInhibitAll"].as_str(), Some("enable"));
+        assert_eq!(ast["BootStrapperForceSelfUpdate"].as_str(), Some("disable"));
+    }
+
+    #[test]
+    fn trailing_content() {
+        let ast = crate::vdf_parse(r#""a" { "x" "1" } "b" { "y" "2" }"#).unwrap();
+        assert_eq!(ast["a"]["x"].as_str(), Some("1"));
+        assert_eq!(ast["b"]["y"].as_str(), Some("2"));
+
+        let err = crate::vdf_parse(r#""a" {} "b""#).unwrap_err();
+        assert_eq!(err.message, "unexpected end of input at line 1 column 11");
+        let err = crate::vdf_parse("\u{feff}\"k\" x").unwrap_err();
+        assert_eq!(err.message, "unexpected token 'x' at line 1 column 5");
+        let err = crate::vdf_parse("\"k\" \"unterminated").unwrap_err();
+        assert_eq!(err.message, "unexpected end of input at line 1 column 18");
+        assert!(crate::vdf_parse("\"k\" \"a\\\"").is_err());
+        assert!(crate::vdf_parse("\"unterminated").is_err());
+        let ast = crate::VdfParserOptions::new().lenient(true).parse("\"a\" { \"x\" \"1\" \"k\" \"cut").unwrap();
+        assert_eq!(ast["a"]["x"].as_str(), Some("1"));
+        let input = "\"AppState\"\n{\n\t\"appid\" \"440\"\n";
+        let err = crate::vdf_parse(input).unwrap_err();
+        assert_eq!(err.key_path, ["AppState"]);
+
+        let ast = crate::VdfParserOptions::new().lenient(true).parse(input).unwrap();
+        assert_eq!(ast["AppState"]["appid"].as_int(), Some(440));
+    }
+
+    #[test]
+    fn unmatched_close() {
+        let input = r#"
+            "key"   "value"
+            }
+        "#;
+        let err = crate::vdf_parse(input).unwrap_err();
+        assert_eq!(err.message, "unmatched '}' at line 3 column 13");
+
+        let ast = crate::VdfParserOptions::new().lenient(true).parse(input).unwrap();
+        assert_eq!(ast["key"].as_str(), Some("value"));
+    }
+
+    #[test]
+    fn find_all() {
+        let ast = crate::vdf_parse(r#"
+            "libraryfolders"
+            {
+                "0" { "path" "/a" "apps" { "10" "1" } }
+                "1" { "path" "/b" }
+            }
+        "#).unwrap();
+        let paths = ast.find_all(|key, _| key == "path");
+        let paths = paths.iter().map(|v| v.as_str()).collect::<Vec<_>>();
+        assert_eq!(paths, [Some("/a"), Some("/b")]);
+    }
+
+    #[test]
+    fn walk() {
+        let ast = crate::vdf_parse(r#"
+            "AppState" { "appid" "10" "UserConfig" { "language" "english" } }
+        "#).unwrap();
+        let mut paths = Vec::new();
+        ast.walk(|path, _| paths.push(path.join("/")));
+        assert_eq!(paths, [
+            "AppState",
+            "AppState/appid",
+            "AppState/UserConfig",
+            "AppState/UserConfig/language",
+        ]);
+    }
+
+    #[test]
+    fn map_keys() {
+        let ast = crate::vdf_parse(r#"
+            "AppState" { "SizeOnDisk" "10" }
+        "#).unwrap();
+        let ast = ast.map_keys(|key| key.to_ascii_lowercase());
+        let keys = ast.find_all(|key, _| key == "sizeondisk");
+        assert_eq!(keys.len(), 1);
+    }
+
+    #[test]
+    fn manifest_names() {
+        use std::path::Path;
+        assert_eq!(crate::manifest_app_id(Path::new("steamapps/appmanifest_440.acf")), Some(crate::AppId(440)));
+        assert_eq!(crate::manifest_app_id(Path::new("steamapps/appmanifest_.acf")), None);
+        assert_eq!(crate::manifest_app_id(Path::new("steamapps/libraryfolders.vdf")), None);
+    }
+
+    #[test]
+    fn null_byte() {
+        let err = crate::vdf_parse("\"key\" \"va\0lue\"").unwrap_err();
+        assert_eq!(err.message, "null byte in string at line 1 column 7");
+        assert_eq!(std::io::Error::from(err).kind(), std::io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn get_index() {
+        let ast = crate::vdf_parse(r#"
+            "list" { "0" "a" "1" "b" }
+        "#).unwrap();
+        assert_eq!(ast["list"].get_index(1).and_then(|v| v.as_str()), Some("b"));
+        assert!(ast["list"].get_index(2).is_none());
+    }
+
+    #[test]
+    fn value_kinds() {
+        let ast = crate::vdf_parse(r#""AppState" { "name" "" "UserConfig" { } }"#).unwrap();
+        let state = &ast["AppState"];
+        assert!(state.is_map() && !state.is_str() && !state.is_null());
+        assert!(state["name"].is_str() && state["name"].as_str() == Some(""));
+        assert!(state["missing"].is_null() && state["missing"].as_map_ref().is_none());
+        assert_eq!(state["UserConfig"].as_map_ref().map(<[_]>::len), Some(0));
+        assert_eq!(state.as_map().map(<[_]>::len), Some(2));
+        assert!(state["name"].as_map().is_none());
+        assert_eq!(state["name"].expect("name must be present").as_str(), Some(""));
+        let err = std::panic::catch_unwind(|| state["missing"].expect("missing must be present").is_map());
+        let msg = err.unwrap_err();
+        assert_eq!(msg.downcast_ref::<String>().map(String::as_str), Some("missing must be present"));
+    }
+
+    #[test]
+    fn serialize() {
+        let input = r#"
+            "libraryfolders"
+            {
+                "0" { "path" "C:\\Program Files (x86)\\Steam" "label" "\"fast\" ドライブ" }
+                "1" { "path" "/mnt/games" "apps" { "440" "1024" "570" { "depth" "3" } } }
+            }
+        "#;
+        let ast = crate::vdf_parse(input).unwrap();
+        let text = crate::vdf_serialize(&ast);
+        assert!(text.contains(r#""C:\\Program Files (x86)\\Steam""#), "{text}");
+        assert!(text.contains(r#""\"fast\" ドライブ""#), "{text}");
+        assert!(text.contains("\n\t\t\t\t\"depth\"\t\t\"3\"\n"), "{text}");
+        assert_eq!(crate::vdf_parse(&text).unwrap(), ast);
+    }
+
+    #[test]
+    fn byte_order_mark() {
+        let ast = crate::vdf_parse("\u{feff}\"libraryfolders\"\r\n{\r\n\t\"0\"  \r\n\t{\r\n\t\t\"path\"\t\t\"C:\\\\Program Files (x86)\\\\Steam\"  \r\n\t}\r\n}\r\n").unwrap();
+        assert_eq!(ast["libraryfolders"]["0"]["path"].as_str(), Some(r"C:\Program Files (x86)\Steam"));
+    }
+
+    #[test]
+    fn count() {
+        let ast = crate::vdf_parse(r#"
+            "key" "a"
+            "KEY" "b"
+            "other" "c"
+        "#).unwrap();
+        assert_eq!(ast.count("key"), 2);
+        assert_eq!(ast.count("other"), 1);
+        assert_eq!(ast.count("missing"), 0);
+    }
+
+    #[test]
+    fn as_list() {
+        let ast = crate::vdf_parse(r#"
+            "list" { "1" "b" "0" "a" "3" "d" }
+        "#).unwrap();
+        let list = ast["list"].as_list();
+        let list = list.iter().map(|v| v.as_str()).collect::<Vec<_>>();
+        assert_eq!(list, [Some("a"), Some("b")]);
+    }
+
+    #[test]
+    fn display() {
+        let ast = crate::vdf_parse(r#"
+            "AppState" { "name" "Say \"hi\"" "UserConfig" { "language" "english" } }
+        "#).unwrap();
+        let text = ast.to_string();
+        assert_eq!(text, "\"AppState\"\n{\n\t\"name\"\t\t\"Say \\\"hi\\\"\"\n\t\"UserConfig\"\n\t{\n\t\t\"language\"\t\t\"english\"\n\t}\n}\n");
+
+        let ast: crate::VdfValue = text.parse().unwrap();
+        assert_eq!(ast["AppState"]["name"].as_str(), Some("Say \"hi\""));
+        assert_eq!(ast["AppState"]["UserConfig"]["language"].as_str(), Some("english"));
+    }
+
+    #[cfg(all(target_os = "linux", target_pointer_width = "64"))]
+    #[test]
+    fn size_exceeds_drive() {
+        use std::fs;
+        let dir = TempDir::new("oversized");
+        fs::create_dir_all(dir.join("common").join("Game")).unwrap();
+        let manifest = r#""AppState" { "appid" "440" "name" "Game" "installdir" "Game" "SizeOnDisk" "9223372036854775807" }"#;
+        fs::write(dir.join("appmanifest_440.acf"), manifest).unwrap();
+        let strict = crate::scan_library(&dir, true, &mut |_| true);
+        let lenient = crate::parse_steamapps_directory(&dir).unwrap();
+        let validated = lenient.iter().map(crate::App::validate).collect::<Vec<_>>();
+        assert!(strict.unwrap().is_empty());
+        assert_eq!(validated, [Err(vec![crate::ValidationError::SizeExceedsDrive])]);
+    }
+
+    #[test]
+    fn steam_cfg() {
+        use std::path::Path;
+        let cfg = "BootStrapperInhibitAll=enable\r\n SteamPath = \"E:\\PortableSteam\" \r\n";
+        assert_eq!(crate::parse_steam_cfg(cfg).as_deref(), Some(Path::new(r"E:\PortableSteam")));
+        assert_eq!(crate::parse_steam_cfg("SteamPath=\nOther=1"), None);
+        assert_eq!(crate::parse_steam_cfg("BootStrapperInhibitAll=enable"), None);
+    }
+
+    #[test]
+    fn steam_library_paths() {
+        use std::path::Path;
+        let library = crate::SteamLibrary {
+            path: Path::new("/mnt/games/SteamLibrary/steamapps").to_path_buf(),
+            apps: Vec::new(),
+            app_count: None,
+        };
+        assert_eq!(library.root(), Path::new("/mnt/games/SteamLibrary"));
+        assert_eq!(library.steamapps_path(), Path::new("/mnt/games/SteamLibrary/steamapps"));
+    }
+
+    #[test]
+    fn no_library_folders() {
+        let ast = crate::vdf_parse(r#""libraryfolders" { }"#).unwrap();
+        assert!(crate::parse_library_paths(&ast).is_empty());
+        let ast = crate::vdf_parse("").unwrap();
+        assert!(crate::parse_library_paths(&ast).is_empty());
+
+        let dir = TempDir::new("empty");
+        assert!(crate::steam_apps_in_library(&dir).unwrap().is_empty());
+    }
+
+    #[test]
+    fn non_ascii_library_paths() {
+        use std::path::Path;
+        let ast = crate::vdf_parse(r#"
+            "libraryfolders"
+            {
+                "0" { "path" "D:\\ゲーム\\Steam" }
+                "1" { "path" "/mnt/игры/Steam" }
+                "contentstatsid" "-4217377696380847751"
+                "other" { "path" "/not/a/library" }
+            }
+        "#).unwrap();
+        let paths = crate::parse_library_paths(&ast);
+        assert_eq!(paths, [
+            Path::new(r"D:\ゲーム\Steam").join("steamapps"),
+            Path::new("/mnt/игры/Steam").join("steamapps"),
+        ]);
+
+        #[cfg(target_os = "windows")]
+        {
+            let components = paths[0].components().map(|c| c.as_os_str()).collect::<Vec<_>>();
+            assert_eq!(components, ["D:", r"\", "ゲーム", "Steam", "steamapps"]);
+        }
+    }
+
+    #[test]
+    fn error_key_path() {
+        let err = crate::vdf_parse(r#"
+            "libraryfolders"
+            {
+                "1"
+                {
+                    "apps" { "10" x }
+                }
+            }
+        "#).unwrap_err();
+        assert_eq!(err.key_path, ["libraryfolders", "1", "apps"]);
+        assert_eq!(err.to_string(), "unexpected token 'x' at line 6 column 35 in \"libraryfolders/1/apps\"");
+    }
+
+    #[test]
+    fn steam_error() {
+        use std::error::Error;
+        let err = crate::SteamError::from(std::io::Error::from(std::io::ErrorKind::PermissionDenied));
+        assert!(matches!(err, crate::SteamError::Io(_)));
+        assert!(err.source().is_some());
+        let err = crate::SteamError::parse(std::path::Path::new("appmanifest_440.acf"), "bad");
+        assert_eq!(err.to_string(), "appmanifest_440.acf: bad");
+        let err = crate::SteamError::NotInstalled("/home/user/.steam/steam".into());
+        assert_eq!(err.to_string(), "failed to find Steam install at /home/user/.steam/steam");
+        let path = std::env::temp_dir().join("steam_find_missing.vdf");
+        let crate::SteamError::Io(err) = crate::read_vdf(&path).unwrap_err() else { panic!() };
+        assert_eq!(err.kind(), std::io::ErrorKind::NotFound);
+        assert!(err.to_string().starts_with(&format!("{}: ", path.display())), "{err}");
+    }
+
+    #[test]
+    fn stream_vdf_events() {
+        use crate::VdfEvent::*;
+        let input = "\u{feff}\"AppState\" { \"name\" \"Say \\\"hi\\\"\" \"UserConfig\" { } }";
+        let events = crate::stream_vdf_events(input.as_bytes())
+            .collect::<std::io::Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(events, [
+            StartMap("AppState".into()),
+            KeyValue("name".into(), "Say \"hi\"".into()),
+            StartMap("UserConfig".into()),
+            EndMap,
+            EndMap,
+        ]);
+
+        let mut events = crate::stream_vdf_events(&b"\"AppState\" { garbage"[..]);
+        assert!(matches!(events.next(), Some(Ok(StartMap(_)))));
+        let err = events.next().unwrap().unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+        assert!(err.to_string().contains("AppState"), "{err}");
+        assert!(err.to_string().contains("unexpected token 'g' at line 1 column 14"), "{err}");
+        assert!(events.next().is_none());
+
+        let input = "\u{feff}\"AppState\"\n{\n\tgarbage\n\t\"name\" \"Caf\u{e9}\"\n";
+        let err = crate::stream_vdf_events(input.as_bytes()).find_map(Result::err).unwrap();
+        assert!(err.to_string().contains("at line 3 column 2"), "{err}");
+        let events = crate::VdfParserOptions::new()
+            .lenient(true)
+            .stream(input.as_bytes())
+            .collect::<std::io::Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(events, [StartMap("AppState".into()), KeyValue("name".into(), "Caf\u{e9}".into()), EndMap]);
+    }
+
+    #[test]
+    fn name_slug() {
+        assert_eq!(app("Team Fortress 2").name_slug(), "team-fortress-2");
+        assert_eq!(app("  Portal 2: The -- Sequel! ").name_slug(), "portal-2-the-sequel");
+        assert_eq!(app("Pokémon™").name_slug(), "pokmon");
+    }
+
+    #[test]
+    fn size_on_disk_human() {
+        let mut app = app("");
+        for (size, human) in [
+            (0, "0 B"),
+            (1023, "1023 B"),
+            (123 * 1024, "123.00 KiB"),
+            (890 * 1024 * 1024 + 512 * 1024, "890.50 MiB"),
+            (13_260_000_000, "12.35 GiB"),
+        ] {
+            app.size_on_disk = size;
+            assert_eq!(app.size_on_disk_human(2), human);
+        }
+        assert_eq!(app.size_on_disk_human(0), "12 GiB");
+    }
+
+    #[test]
+    fn app_filter() {
+        use crate::AppFilter;
+        let mut portal = app("Portal 2");
+        portal.size_on_disk = 1 << 30;
+        portal.state_flags = crate::AppUpdateState::FULLY_INSTALLED;
+        let mut tf2 = app("Team Fortress 2");
+        tf2.state_flags = crate::AppUpdateState::UPDATE_REQUIRED;
+
+        let filter = AppFilter::by_name("portal").and(AppFilter::installed());
+        assert!(filter.matches(&portal));
+        assert!(!filter.matches(&tf2));
+
+        let filter = AppFilter::by_min_size(1 << 20).or(AppFilter::update_required());
+        assert!(filter.matches(&portal) && filter.matches(&tf2));
+
+        let filter = !AppFilter::by_name("PORTAL");
+        assert!(!filter.matches(&portal));
+        assert!(filter.matches(&tf2));
+    }
+
+    #[test]
+    fn platform() {
+        use crate::ExecutablePlatform;
+        use std::fs;
+        let dir = TempDir::new("platform");
+        let mut app = app("Game");
+        app.path = dir.to_path_buf();
+        fs::create_dir_all(dir.join("bin")).unwrap();
+        assert_eq!(app.platform(), None);
+        fs::write(dir.join("bin").join("game"), b"\x7fELF\x02\x01").unwrap();
+        fs::write(dir.join("readme"), b"not a binary").unwrap();
+        assert_eq!(app.platform(), Some(ExecutablePlatform::Linux));
+        fs::write(dir.join("Game.exe"), b"MZ").unwrap();
+        assert_eq!(app.platform(), Some(ExecutablePlatform::MultiPlatform));
+        assert_eq!(app.scripts_path(), None);
+        fs::create_dir(dir.join("scripts")).unwrap();
+        assert_eq!(app.scripts_path(), Some(dir.join("scripts")));
+    }
+
+    #[test]
+    fn optional_fields() {
+        use std::fs;
+        let dir = TempDir::new("optional");
+        fs::write(dir.join("appmanifest_1628350.acf"), r#""AppState" { "appid" "1628350" "installdir" "SteamLinuxRuntime_sniper" }"#).unwrap();
+        fs::write(dir.join("appmanifest_1.acf"), r#""AppState" { "appid" "1" "name" "No installdir" }"#).unwrap();
+        let apps = crate::parse_steamapps_directory(&dir).unwrap();
+        assert_eq!(apps.len(), 1);
+        assert_eq!(apps[0].app_id, crate::AppId(1628350));
+        assert_eq!(apps[0].name, "");
+        assert_eq!(apps[0].size_on_disk, 0);
+        assert_eq!(apps[0].installdir, "SteamLinuxRuntime_sniper");
+        assert_eq!(apps[0].path, dir.join("common").join("SteamLinuxRuntime_sniper"));
+        assert!(!apps[0].path.exists());
+        assert_eq!(apps[0].library_path, *dir);
+    }
+
+    #[test]
+    fn installer_info() {
+        let dir = TempDir::new("installer");
+        let path = dir.join("appmanifest_440.acf");
+        let mut app = app("Game");
+        app.acf_path = path.clone();
+        assert_eq!(app.installer_info(), None);
+        std::fs::write(&path, r#""AppState" { "BytesToDownload" "0" "BytesToStage" "0" }"#).unwrap();
+        assert_eq!(app.installer_info(), None);
+        std::fs::write(&path, r#""AppState" { "BytesToDownload" "2048" "BytesDownloaded" "1024" "BytesToStage" "4096" }"#).unwrap();
+        let installer = app.installer_info();
+        assert!(app.acf_modification_time().unwrap() <= std::time::SystemTime::now());
+        std::fs::remove_file(&path).unwrap();
+        assert!(app.acf_modification_time().is_err());
+        assert_eq!(installer, Some(crate::AppInstaller {
+            bytes_downloaded: 1024,
+            bytes_to_download: 2048,
+            bytes_staged: 0,
+            bytes_to_stage: 4096,
+        }));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn proton_prefix_path() {
+        let dir = TempDir::new("compatdata");
+        let mut app = app("Game");
+        app.app_id = crate::AppId(440);
+        app.library_path = dir.to_path_buf();
+        assert_eq!(crate::proton_prefix_path(&app), None);
+        let pfx = dir.join("compatdata").join("440").join("pfx");
+        std::fs::create_dir_all(&pfx).unwrap();
+        assert_eq!(crate::proton_prefix_path(&app), Some(pfx));
+    }
+
+    #[test]
+    fn update_state() {
+        use crate::AppUpdateState;
+        let mut app = app("Game");
+        for (flags, state) in [
+            (4, AppUpdateState::FullyInstalled),
+            (6, AppUpdateState::NeedsUpdate),
+            (1026, AppUpdateState::Downloading),
+            (1542, AppUpdateState::UpdatePaused),
+            (131076, AppUpdateState::Verifying),
+            (36, AppUpdateState::FilesCorrupt),
+            (1, AppUpdateState::Unknown(1)),
+        ] {
+            app.state_flags = flags;
+            assert_eq!(app.update_state(), state, "{flags}");
+        }
+    }
+
+    #[test]
+    fn app_type() {
+        use crate::AppType;
+        assert_eq!(AppType::parse("Game"), AppType::Game);
+        assert_eq!(AppType::parse("DLC"), AppType::Dlc);
+        assert_eq!(AppType::parse("tool"), AppType::Tool);
+        assert_eq!(AppType::parse("Music"), AppType::Other("Music".to_string()));
+        assert_eq!(AppType::parse(""), AppType::Other(String::new()));
+    }
+
+    #[test]
+    fn app_id() {
+        use crate::AppId;
+        const TF2: AppId = AppId::new(440);
+        assert_eq!("440".parse::<AppId>(), Ok(TF2));
+        assert!("-440".parse::<AppId>().is_err());
+        assert_eq!(TF2.to_string(), "440");
+        assert_eq!(u64::from(TF2), 440);
+        assert_eq!(AppId::from(440), TF2);
+        assert_eq!(TF2.0, 440);
+    }
+
+    #[test]
+    fn app_id_eq() {
+        let mut app = app("Team Fortress 2");
+        app.app_id = crate::AppId(440);
+        assert!(app == 440);
+        assert!(440 == app);
+        assert!(app == crate::AppId(440));
+        assert!(crate::AppId(440) == app);
+        assert!(app != 570);
+        assert!(app.app_id_eq(440) && app.app_id_eq(crate::AppId(440)));
+
+        let mut moved = app.clone();
+        moved.path = "/mnt/games/steamapps/common/Team Fortress 2".into();
+        assert_eq!(moved, app);
+        assert_eq!(std::collections::HashSet::from([app, moved]).len(), 1);
+    }
+
+    #[test]
+    fn case_insensitive_keys() {
+        let ast = crate::vdf_parse(r#"
+            "appstate"
+            {
+                "APPID"         "440"
+                "Name"          "Team Fortress 2"
+                "sizeondisk"    "1024"
+                "InstallDir"    "Team Fortress 2"
+            }
+        "#).unwrap();
+        let state = &ast["AppState"];
+        assert_eq!(state["appid"].as_int(), Some(440));
+        assert_eq!(state["name"].as_str(), Some("Team Fortress 2"));
+        assert_eq!(state["SizeOnDisk"].as_int(), Some(1024));
+        assert_eq!(state["sizeonDisk"].as_int(), Some(1024));
+        assert_eq!(state["installdir"].as_str(), Some("Team Fortress 2"));
+    }
+
+    #[cfg(all(not(target_os = "windows"), not(target_os = "macos")))]
+    #[test]
+    fn data_home() {
+        use std::ffi::OsStr;
+        use std::path::Path;
+        let home = OsStr::new("/home/user");
+        assert_eq!(crate::data_home(home, None), Path::new("/home/user/.local/share"));
+        assert_eq!(crate::data_home(home, Some("/data".into())), Path::new("/data"));
+        assert_eq!(crate::data_home(home, Some("data".into())), Path::new("/home/user/.local/share"));
+    }
+
+    #[cfg(all(target_os = "linux", target_pointer_width = "64"))]
+    #[test]
+    fn drive_capacity() {
+        let capacity = crate::drive_capacity(std::path::Path::new("/"));
+        assert!(capacity.is_some_and(|capacity| capacity > 0), "{capacity:?}");
+        assert_eq!(crate::drive_capacity(std::path::Path::new("/does/not/exist")), None);
+    }
+
+    #[cfg(all(not(target_os = "windows"), not(target_os = "macos")))]
+    #[test]
+    fn steam_dir_candidates() {
+        use std::ffi::OsStr;
+        use std::path::Path;
+        let candidates = crate::steam_dir_candidates(OsStr::new("/home/user"), None);
+        assert_eq!(candidates, [
+            Path::new("/home/user/.local/share/Steam"),
+            Path::new("/home/user/.steam/steam"),
+            Path::new("/home/user/.steam/root"),
+            Path::new("/home/user/.var/app/com.valvesoftware.Steam/.steam/steam"),
+            Path::new("/home/user/snap/steam/common/.steam/steam"),
+        ]);
+        let candidates = crate::steam_dir_candidates(OsStr::new("/home/user"), Some("/data".into()));
+        assert_eq!(&candidates[..3], [
+            Path::new("/data/Steam"),
+            Path::new("/home/user/.local/share/Steam"),
+            Path::new("/home/user/.steam/steam"),
+        ]);
+    }
+
+    #[test]
+    fn builder() {
+        use std::fmt::Write;
+        let mut builder = crate::VdfBuilder::new();
+        builder.begin("AppState").pair("appid", "440").pair("name", "Say \"hi\"");
+        let installdir = "Team Fortress 2";
+        writeln!(builder, "\t\"installdir\"\t\t\"{installdir}\"").unwrap();
+        builder.end();
+        let text = builder.finish();
+
+        let ast = crate::vdf_parse(&text).unwrap();
+        assert_eq!(ast["AppState"]["appid"].as_int(), Some(440));
+        assert_eq!(ast["AppState"]["name"].as_str(), Some("Say \"hi\""));
+        assert_eq!(ast["AppState"]["installdir"].as_str(), Some("Team Fortress 2"));
+        assert_eq!(text, ast.to_string());
+    }
+
+    #[test]
+    fn depots() {
+        let ast = crate::vdf_parse(r#"
+            "AppState"
+            {
+                "InstalledDepots"
+                {
+                    "441" { "manifest" "7707612755534230539" "size" "1024" }
+                    "232251" { "manifest" "garbage" }
+                }
+            }
+        "#).unwrap();
+        let mut app = app("Team Fortress 2");
+        app.installed_depots = crate::parse_depots(&ast["AppState"]);
+        assert_eq!(app.installed_depots, [crate::DepotInfo {
+            depot_id: 441,
+            manifest_id: 7707612755534230539,
+            size: 1024,
+        }]);
+        assert_eq!(app.depot_hash(441).as_deref(), Some("6af6f0f6e7e3340b"));
+        assert_eq!(app.depot_hash(232251), None);
+        assert_eq!(app.depot_count(), 1);
+    }
+
+    #[test]
+    fn binary() {
+        let mut input = Vec::new();
+        input.extend(b"\x00UserLocalConfigStore\x00");
+        input.extend(b"\x02Playtime\x00");
+        input.extend(90i32.to_le_bytes());
+        input.extend(b"\x01name\x00Half-Life\x00\x08\x08");
+        let ast = crate::vdf_parse_binary(&input).unwrap();
+        assert_eq!(ast["userlocalconfigstore"]["Playtime"].as_int(), Some(90));
+        assert_eq!(ast["UserLocalConfigStore"].iter().count(), 2);
+        assert!(crate::vdf_parse_binary(b"\x09key\x00").is_err());
+    }
+
+    #[test]
+    fn users() {
+        let ast = crate::vdf_parse(r#"
+            "users"
+            {
+                "76561197960287930"
+                {
+                    "AccountName"   "gaben"
+                    "PersonaName"   "Rabscuttle"
+                    "MostRecent"    "1"
+                }
+                "76561197960265729" { "AccountName" "other" "MostRecent" "0" }
+                "not an id" { "AccountName" "skipped" }
+            }
+        "#).unwrap();
+        let users = crate::parse_users(&ast);
+        assert_eq!(users.len(), 2);
+        let gaben = users.iter().find(|user| user.most_recent).unwrap();
+        assert_eq!(gaben.steam_id, 76561197960287930);
+        assert_eq!(gaben.account_name, "gaben");
+        assert_eq!(gaben.persona_name, "Rabscuttle");
+        assert_eq!(gaben.account_id(), 22202);
+        assert!(users.iter().any(|user| user.account_name == "other" && user.persona_name.is_empty()));
+    }
+
+    #[test]
+    fn shortcuts() {
+        let mut input = Vec::new();
+        input.extend(b"\x00shortcuts\x00\x000\x00");
+        input.extend(b"\x02appid\x00");
+        input.extend((-5i32).to_le_bytes());
+        input.extend(b"\x01AppName\x00Emulator\x00");
+        input.extend(b"\x01Exe\x00\"/usr/bin/emu\"\x00");
+        input.extend(b"\x02IsHidden\x00\x01\x00\x00\x00");
+        input.extend(b"\x00tags\x00\x010\x00Favorite\x00\x011\x00Retro\x00\x08");
+        input.extend(b"\x08\x08\x08");
+        let shortcuts = crate::parse_shortcuts(crate::vdf_parse_binary(&input).unwrap());
+        assert_eq!(shortcuts.len(), 1);
+        assert_eq!(shortcuts[0].app_id, -5i32 as u32);
+        assert_eq!(shortcuts[0].name, "Emulator");
+        assert_eq!(shortcuts[0].exe, "\"/usr/bin/emu\"");
+        assert_eq!(shortcuts[0].start_dir, "");
+        assert_eq!(shortcuts[0].tags(), ["Favorite", "Retro"]);
+        assert!(shortcuts[0].hide_desktop_shortcut());
+
+        let err = crate::vdf_parse_binary(b"\x00shortcuts\x00\x000\x00\x02appid\x00\x01").unwrap_err();
+        assert_eq!(err.key_path, ["shortcuts", "0"]);
+    }
+
+    #[cfg(feature = "typed-arena")]
+    #[test]
+    fn arena() {
+        use std::borrow::Cow;
+        let arena = typed_arena::Arena::new();
+        let input = r#""AppState" { "name" "Say \"hi\"" "installdir" "Game" }"#;
+        let ast = crate::vdf_parse_in_arena(input, &arena).unwrap();
+        assert!(matches!(&ast["AppState"]["name"], crate::VdfValue::Str(Cow::Borrowed("Say \"hi\""))));
+        assert_eq!(ast["AppState"]["installdir"].as_str(), Some("Game"));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn deserialize() {
+        use serde::Deserialize;
+
+        #[derive(Deserialize, Debug, PartialEq)]
+        enum Universe {
+            Public,
+        }
+
+        #[derive(Deserialize, Debug)]
+        struct UserConfig {
+            language: String,
+        }
+
+        #[derive(Deserialize, Debug)]
+        struct AppState {
+            appid: u64,
+            name: String,
+            sizeondisk: u64,
+            #[serde(rename = "AutoUpdatePaused")]
+            paused: bool,
+            universe: Universe,
+            buildid: Option<u64>,
+            userconfig: UserConfig,
+            tags: Vec<String>,
+        }
+
+        let ast = crate::vdf_parse(r#"
+            "AppState"
+            {
+                "appid"     "440"
+                "name"      "Team Fortress 2"
+                "SizeOnDisk"    "26514705196"
+                "AutoUpdatePaused"  "1"
+                "Universe"  "Public"
+                "UserConfig" { "language" "english" }
+                "tags" { "0" "FPS" "1" "Free to Play" }
+            }
+        "#).unwrap();
+        let state = AppState::deserialize(&ast["AppState"]).unwrap();
+        assert_eq!(state.appid, 440);
+        assert_eq!(state.name, "Team Fortress 2");
+        assert_eq!(state.sizeondisk, 26514705196);
+        assert!(state.paused);
+        assert_eq!(state.universe, Universe::Public);
+        assert_eq!(state.buildid, None);
+        assert_eq!(state.userconfig.language, "english");
+        assert_eq!(state.tags, ["FPS", "Free to Play"]);
+
+        #[derive(Deserialize, Debug)]
+        #[allow(dead_code)]
+        struct Outer {
+            inner: Inner,
+        }
+
+        #[derive(Deserialize, Debug)]
+        #[allow(dead_code)]
+        struct Inner {
+            size: u64,
+        }
+
+        #[derive(Deserialize, Debug)]
+        struct Borrowed<'a> {
+            name: &'a str,
+            escaped: &'a str,
+        }
+
+        let ast = crate::vdf_parse(r#""name" "Game" "escaped" "Say \"hi\"""#).unwrap();
+        let borrowed = Borrowed::deserialize(&ast).unwrap();
+        assert_eq!(borrowed.name, "Game");
+        assert_eq!(borrowed.escaped, "Say \"hi\"");
+
+        let ast = crate::vdf_parse(r#""Inner" { "size" "big" }"#).unwrap();
+        let err = Outer::deserialize(&ast).unwrap_err();
+        assert_eq!(err.key_path, ["Inner", "size"]);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn vdf_to_string() {
+        use serde::Serialize;
+
+        #[derive(Serialize)]
+        enum Universe {
+            Public,
+        }
+
+        #[derive(Serialize)]
+        #[serde(rename_all = "PascalCase")]
+        struct AppState {
+            #[serde(rename = "appid")]
+            app_id: u64,
+            name: &'static str,
+            auto_update_paused: bool,
+            universe: Universe,
+            build_id: Option<u64>,
+            installed_depots: std::collections::BTreeMap<u64, u64>,
+            tags: Vec<&'static str>,
+        }
+
+        #[derive(Serialize)]
+        struct Manifest {
+            #[serde(rename = "AppState")]
+            state: AppState,
+        }
+
+        let text = crate::vdf_to_string(&Manifest {
+            state: AppState {
+                app_id: 440,
+                name: "Say \"hi\"",
+                auto_update_paused: true,
+                universe: Universe::Public,
+                build_id: None,
+                installed_depots: [(441, 5164243795930793220)].into(),
+                tags: vec!["FPS", "Free to Play"],
+            },
+        }).unwrap();
+        assert_eq!(text, concat!(
+            "\"AppState\"\n{\n",
+            "\t\"appid\"\t\t\"440\"\n",
+            "\t\"Name\"\t\t\"Say \\\"hi\\\"\"\n",
+            "\t\"AutoUpdatePaused\"\t\t\"1\"\n",
+            "\t\"Universe\"\t\t\"Public\"\n",
+            "\t\"InstalledDepots\"\n\t{\n\t\t\"441\"\t\t\"5164243795930793220\"\n\t}\n",
+            "\t\"Tags\"\n\t{\n\t\t\"0\"\t\t\"FPS\"\n\t\t\"1\"\t\t\"Free to Play\"\n\t}\n",
+            "}\n",
+        ));
+        let ast = crate::vdf_parse(&text).unwrap();
+        assert_eq!(ast["AppState"]["name"].as_str(), Some("Say \"hi\""));
+
+        assert!(crate::vdf_to_string(&440).is_err());
+    }
+}
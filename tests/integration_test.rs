@@ -0,0 +1,42 @@
+use std::path::Path;
+
+use steam_find::AppId;
+
+// cargo runs integration tests from the package root, which the relative
+// library paths in the fixture's `libraryfolders.vdf` rely on
+#[test]
+fn fixture_install() {
+    std::env::set_var("STEAM_DIR", "tests/fixtures/steam");
+
+    let libraries = steam_find::steam_libraries().unwrap();
+    assert_eq!(libraries, [
+        Path::new("tests/fixtures/steam/steamapps"),
+        Path::new("tests/fixtures/library/steamapps"),
+    ]);
+
+    let counts = steam_find::steam_libraries_with_apps()
+        .unwrap()
+        .iter()
+        .map(|library| library.total_app_count())
+        .collect::<Vec<_>>();
+    assert_eq!(counts, [Some(1), Some(1)]);
+
+    let apps = steam_find::steam_apps().unwrap();
+    let ids = apps.iter().map(|app| app.app_id).collect::<Vec<_>>();
+    assert_eq!(ids, [AppId(440), AppId(570)]);
+    assert_eq!(apps[0].name, "Team Fortress 2");
+    assert_eq!(apps[0].path, Path::new("tests/fixtures/steam/steamapps/common/Team Fortress 2"));
+    assert!(apps[0].is_fully_installed());
+    assert_eq!(apps[0].depot_count(), 1);
+    assert_eq!(apps[1].library_path, Path::new("tests/fixtures/library/steamapps"));
+    assert_eq!(apps[1].update_state(), steam_find::AppUpdateState::NeedsUpdate);
+
+    let newer = steam_find::steam_apps_with_min_id(500).unwrap();
+    assert_eq!(newer.iter().map(|app| app.app_id).collect::<Vec<_>>(), [AppId(570)]);
+
+    let dota = steam_find::get_steam_app(570).unwrap();
+    assert_eq!(dota.name, "Dota 2");
+    assert_eq!(dota.installdir, "dota 2 beta");
+    assert_eq!(dota.build_id, 14465802);
+    assert!(steam_find::get_steam_app(730).is_err());
+}
@@ -0,0 +1,19 @@
+#![cfg(feature = "serde")]
+
+use std::path::Path;
+
+use steam_find::App;
+use steam_find::AppId;
+
+#[test]
+fn json_round_trip() {
+    let apps = steam_find::parse_steamapps_directory(Path::new("tests/fixtures/steam/steamapps")).unwrap();
+    let app = &apps[0];
+    let json = serde_json::to_string(app).unwrap();
+    assert!(json.contains(r#""app_id":440"#), "{json}");
+    let back: App = serde_json::from_str(&json).unwrap();
+    assert_eq!(back.app_id, AppId(440));
+    assert_eq!(back.name, app.name);
+    assert_eq!(back.path, app.path);
+    assert_eq!(back.installed_depots, app.installed_depots);
+}
@@ -0,0 +1,76 @@
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+
+use steam_find::AppId;
+
+// a fresh directory under the system temp dir, removed again on drop even
+// when an assertion fails first
+struct TempDir(PathBuf);
+
+impl TempDir {
+    fn new(name: &str) -> TempDir {
+        let path = std::env::temp_dir().join(format!("steam_find_{name}_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&path);
+        fs::create_dir_all(&path).unwrap();
+        TempDir(path)
+    }
+}
+
+impl std::ops::Deref for TempDir {
+    type Target = Path;
+
+    fn deref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+// `STEAM_DIR` is process wide, so this is the only test in this file
+#[test]
+fn steam_dir_override() {
+    let dir = TempDir::new("override");
+    let steamapps = dir.join("steamapps");
+    let moved = dir.join("moved");
+    fs::create_dir_all(&steamapps).unwrap();
+    fs::create_dir_all(moved.join("steamapps")).unwrap();
+    let libraryfolders = format!(r#""libraryfolders" {{ "1" {{ "path" "{}" }} }}"#, moved.display());
+    fs::write(steamapps.join("libraryfolders.vdf"), libraryfolders).unwrap();
+    fs::write(steamapps.join("appmanifest_440.acf"), r#""AppState" { "appid" "440" "installdir" "Team Fortress 2" "SizeOnDisk" "1024" }"#).unwrap();
+    fs::write(moved.join("steamapps").join("appmanifest_440.acf"), r#""AppState" { "appid" "440" "installdir" "Team Fortress 2" "SizeOnDisk" "10" }"#).unwrap();
+
+    std::env::set_var("STEAM_DIR", &*dir);
+    assert_eq!(steam_find::steam_dir_from_env().as_deref(), Some(&*dir));
+    assert_eq!(steam_find::steam_dir().unwrap(), *dir);
+
+    // the copy in the default library wins over the one left in `moved`
+    let apps = steam_find::steam_apps().unwrap();
+    assert_eq!(apps.len(), 1);
+    assert_eq!(apps[0].app_id, AppId(440));
+    assert_eq!(apps[0].size_on_disk, 1024);
+    assert_eq!(steam_find::steam_apps_parallel().unwrap(), apps);
+    assert_eq!(steam_find::get_steam_apps(&[440u64, 730]).unwrap(), apps);
+    assert_eq!(steam_find::parse_steamapps_directory(&steamapps).unwrap(), apps);
+
+    // the default library reached through a symlink is the one listed
+    #[cfg(unix)]
+    {
+        let link = std::env::temp_dir().join(format!("steam_find_override_link_{}", std::process::id()));
+        let _ = fs::remove_file(&link);
+        std::os::unix::fs::symlink(&*dir, &link).unwrap();
+        let libraryfolders = format!(r#""libraryfolders" {{ "0" {{ "path" "{}" }} }}"#, dir.display());
+        fs::write(steamapps.join("libraryfolders.vdf"), libraryfolders).unwrap();
+        std::env::set_var("STEAM_DIR", &link);
+        let libraries = steam_find::steam_libraries();
+        fs::remove_file(&link).unwrap();
+        assert_eq!(libraries.unwrap(), [steamapps]);
+    }
+
+    std::env::remove_var("STEAM_DIR");
+    assert_eq!(steam_find::steam_dir_from_env(), None);
+}